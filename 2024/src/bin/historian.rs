@@ -0,0 +1,470 @@
+//! Tools for location lists too large for the puzzle runner, which reads its whole input into
+//! memory. Inputs are read line by line and sorted on disk, see
+//! [`advent_of_code_2024::historian::external`].
+
+use advent_of_code_2024::historian::external::{self, ExternalSort, IdCounts, Side};
+use advent_of_code_2024::historian::metrics::{self, find_metric, Score, METRICS};
+use advent_of_code_2024::historian::reconcile;
+use aoc_common::args::{parse_number, parse_value};
+use aoc_common::error::{Error, Result};
+use aoc_common::input::open_path_or_stdin;
+use aoc_common::report::{diagnostic, Format, JsonObject};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "Usage: historian [--format text|json] <command> [--chunk-size <pairs>] [--temp-dir <dir>] <input file | ->
+  historian totals [--metric <name>[,<name>...]]
+  historian reconcile --output <dir> [--export csv|json] [--top <N>]";
+
+/// File format of the reconciliation tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Export {
+    Csv,
+    /// One JSON object per line.
+    Json,
+}
+
+impl FromStr for Export {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, ()> {
+        match s {
+            "csv" => Ok(Export::Csv),
+            "json" => Ok(Export::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Options of the `reconcile` subcommand.
+#[derive(Debug, PartialEq, Eq)]
+struct ReconcileOptions {
+    /// Directory the tables are written to.
+    output: PathBuf,
+    export: Export,
+    /// How many of the largest distances are kept.
+    top: usize,
+}
+
+/// A subcommand parsed from the command line.
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    /// Prints the named metrics of [`METRICS`], in order.
+    Totals(Vec<&'static str>),
+    /// Writes per-ID counts, one-sided IDs, the largest distances and a distance histogram.
+    Reconcile(ReconcileOptions),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    command: Command,
+    sort: ExternalSort,
+    input: String,
+    format: Format,
+}
+
+fn usage_error(message: impl Into<String>) -> Error {
+    Error::Usage(message.into())
+}
+
+fn parse_args(args: &[String]) -> Result<Args> {
+    let mut format = Format::Text;
+    let mut args_without_format = Vec::with_capacity(args.len());
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--format" {
+            let value = parse_value(iter.next(), "--format")?;
+            format = value.parse().map_err(|_| {
+                usage_error(format!("--format must be text or json, got '{}'", value))
+            })?;
+        } else {
+            args_without_format.push(arg.clone());
+        }
+    }
+
+    let (subcommand, rest) = args_without_format
+        .split_first()
+        .ok_or_else(|| usage_error("missing subcommand"))?;
+    let mut sort = ExternalSort::default();
+    let mut input = None;
+    let mut rest_without_common = Vec::with_capacity(rest.len());
+
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--chunk-size" => sort.chunk_size = parse_number(iter.next(), "--chunk-size")?,
+            "--temp-dir" => sort.temp_dir = PathBuf::from(parse_value(iter.next(), "--temp-dir")?),
+            flag if flag.starts_with("--") => {
+                rest_without_common.push(flag.to_string());
+                if let Some(value) = iter.next() {
+                    rest_without_common.push(value.clone());
+                }
+            }
+            path => {
+                if input.replace(path.to_string()).is_some() {
+                    return Err(usage_error("give only one input file"));
+                }
+            }
+        }
+    }
+    if sort.chunk_size == 0 {
+        return Err(usage_error("--chunk-size must be at least 1"));
+    }
+
+    let command = match subcommand.as_str() {
+        "totals" => parse_totals(&rest_without_common)?,
+        "reconcile" => parse_reconcile(&rest_without_common)?,
+        other => return Err(usage_error(format!("unknown subcommand '{}'", other))),
+    };
+
+    Ok(Args {
+        command,
+        sort,
+        input: input.ok_or_else(|| usage_error("missing input file"))?,
+        format,
+    })
+}
+
+/// Parses the options of `totals`: `--metric` may be repeated or list several names, and
+/// defaults to the two puzzle answers.
+fn parse_totals(args: &[String]) -> Result<Command> {
+    let mut names = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--metric" => {
+                for name in parse_value(iter.next(), "--metric")?.split(',') {
+                    let entry = find_metric(name).map_err(|e| match e {
+                        Error::InvalidInput(message) => usage_error(message),
+                        e => e,
+                    })?;
+                    if !names.contains(&entry.name) {
+                        names.push(entry.name);
+                    }
+                }
+            }
+            other => return Err(usage_error(format!("unknown option '{}'", other))),
+        }
+    }
+    if names.is_empty() {
+        names = vec!["distance", "similarity"];
+    }
+
+    Ok(Command::Totals(names))
+}
+
+/// Parses the options of `reconcile`; every one of them takes a value.
+fn parse_reconcile(args: &[String]) -> Result<Command> {
+    let mut output = None;
+    let mut export = Export::Csv;
+    let mut top = 10;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" => output = Some(PathBuf::from(parse_value(iter.next(), "--output")?)),
+            "--export" => {
+                let value = parse_value(iter.next(), "--export")?;
+                export = value.parse().map_err(|_| {
+                    usage_error(format!("--export must be csv or json, got '{}'", value))
+                })?;
+            }
+            "--top" => top = parse_number(iter.next(), "--top")?,
+            other => return Err(usage_error(format!("unknown option '{}'", other))),
+        }
+    }
+
+    Ok(Command::Reconcile(ReconcileOptions {
+        output: output.ok_or_else(|| usage_error("missing --output"))?,
+        export,
+        top,
+    }))
+}
+
+fn totals(args: &Args, names: &[&'static str]) -> Result<()> {
+    let entries: Vec<_> = names
+        .iter()
+        .map(|name| find_metric(name))
+        .collect::<Result<_>>()?;
+    let mut scores: Vec<_> = entries.iter().map(|entry| (entry.new)()).collect();
+    let lists = external::sort_lists(open_path_or_stdin(&args.input)?, &args.sort)?;
+    metrics::score_sorted(&lists, &mut scores)?;
+
+    let width = METRICS
+        .iter()
+        .map(|entry| entry.label.len())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    match args.format {
+        Format::Text => {
+            println!("{:<width$}{}", "Pairs", lists.pairs(), width = width);
+            for (entry, score) in entries.iter().zip(&scores) {
+                println!("{:<width$}{}", entry.label, score.value(), width = width);
+            }
+        }
+        Format::Json => {
+            let mut object = JsonObject::new().number("pairs", lists.pairs());
+            for (entry, score) in entries.iter().zip(&scores) {
+                object = match score.value() {
+                    Score::Integer(n) => object.number(entry.name, n),
+                    Score::Real(x) => object.number(entry.name, x),
+                };
+            }
+            println!("{}", object);
+        }
+    }
+    Ok(())
+}
+
+/// One cell of an exported table.
+enum Cell<'a> {
+    Number(i128),
+    Text(&'a str),
+}
+
+/// A table written row by row as CSV with a header, or as one JSON object per row.
+struct Table {
+    path: PathBuf,
+    export: Export,
+    columns: &'static [&'static str],
+    writer: BufWriter<File>,
+}
+
+impl Table {
+    fn create(
+        dir: &Path,
+        name: &str,
+        export: Export,
+        columns: &'static [&'static str],
+    ) -> Result<Self> {
+        let extension = match export {
+            Export::Csv => "csv",
+            Export::Json => "json",
+        };
+        let path = dir.join(format!("{}.{}", name, extension));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        if export == Export::Csv {
+            writeln!(writer, "{}", columns.join(","))?;
+        }
+        Ok(Table {
+            path,
+            export,
+            columns,
+            writer,
+        })
+    }
+
+    fn row(&mut self, cells: &[Cell]) -> Result<()> {
+        match self.export {
+            Export::Csv => {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Number(n) => n.to_string(),
+                        Cell::Text(s) => s.to_string(),
+                    })
+                    .collect();
+                writeln!(self.writer, "{}", cells.join(","))?;
+            }
+            Export::Json => {
+                let mut object = JsonObject::new();
+                for (&column, cell) in self.columns.iter().zip(cells) {
+                    object = match cell {
+                        Cell::Number(n) => object.number(column, n),
+                        Cell::Text(s) => object.string(column, s),
+                    };
+                }
+                writeln!(self.writer, "{}", object)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the table and returns where it was written.
+    fn finish(mut self) -> Result<String> {
+        self.writer.flush()?;
+        Ok(self.path.to_string_lossy().into_owned())
+    }
+}
+
+fn reconcile(args: &Args, options: &ReconcileOptions) -> Result<()> {
+    let lists = external::sort_lists(open_path_or_stdin(&args.input)?, &args.sort)?;
+    fs::create_dir_all(&options.output)?;
+    let table = |name, columns| Table::create(&options.output, name, options.export, columns);
+
+    let mut ids = table("ids", &["id", "left", "right"])?;
+    let mut one_sided = table("one-sided", &["id", "list", "count"])?;
+    let reconciliation = reconcile::reconcile(&lists, options.top, |counts: IdCounts| {
+        let (id, left, right) = (counts.id.into(), counts.left.into(), counts.right.into());
+        ids.row(&[Cell::Number(id), Cell::Number(left), Cell::Number(right)])?;
+        if let Some(side) = counts.only_in() {
+            let count = match side {
+                Side::Left => left,
+                Side::Right => right,
+            };
+            one_sided.row(&[
+                Cell::Number(id),
+                Cell::Text(side.name()),
+                Cell::Number(count),
+            ])?;
+        }
+        Ok(())
+    })?;
+
+    let mut largest = table("largest", &["pair", "left", "right", "distance"])?;
+    for pair in &reconciliation.largest {
+        largest.row(&[
+            Cell::Number(pair.index.into()),
+            Cell::Number(pair.left.into()),
+            Cell::Number(pair.right.into()),
+            Cell::Number(pair.distance()),
+        ])?;
+    }
+    let mut histogram = table("histogram", &["from", "to", "pairs"])?;
+    for bucket in &reconciliation.histogram {
+        histogram.row(&[
+            Cell::Number(bucket.from),
+            Cell::Number(bucket.to),
+            Cell::Number(bucket.pairs.into()),
+        ])?;
+    }
+
+    let files = [
+        ids.finish()?,
+        one_sided.finish()?,
+        largest.finish()?,
+        histogram.finish()?,
+    ];
+    match args.format {
+        Format::Text => {
+            println!("Pairs               {}", reconciliation.pairs);
+            println!("Distinct IDs        {}", reconciliation.distinct_ids);
+            println!("Only in left list   {}", reconciliation.left_only);
+            println!("Only in right list  {}", reconciliation.right_only);
+            for file in &files {
+                println!("wrote {}", file);
+            }
+        }
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("pairs", reconciliation.pairs)
+                .number("distinct_ids", reconciliation.distinct_ids)
+                .number("left_only", reconciliation.left_only)
+                .number("right_only", reconciliation.right_only)
+                .strings("files", &files)
+        ),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match &args.command {
+        Command::Totals(names) => totals(&args, names),
+        Command::Reconcile(options) => reconcile(&args, options),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", diagnostic(args.format, "error", &e.to_string()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_totals() {
+        let parsed = parse_args(&args(
+            "--format json totals --chunk-size 1000 --temp-dir /scratch -",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            Args {
+                command: Command::Totals(vec!["distance", "similarity"]),
+                sort: ExternalSort {
+                    chunk_size: 1000,
+                    temp_dir: PathBuf::from("/scratch"),
+                    ..ExternalSort::default()
+                },
+                input: "-".to_string(),
+                format: Format::Json,
+            }
+        );
+
+        assert!(parse_args(&args("totals")).is_err());
+        assert!(parse_args(&args("totals --chunk-size 0 in.txt")).is_err());
+        assert!(parse_args(&args("totals a.txt b.txt")).is_err());
+        assert!(parse_args(&args("totals --top 3 in.txt")).is_err());
+        assert!(parse_args(&args("sort in.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_totals_metrics() {
+        let parsed = parse_args(&args(
+            "totals --metric emd,jaccard --metric squared,emd in.txt",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Totals(vec!["emd", "jaccard", "squared"])
+        );
+
+        let err = parse_args(&args("totals --metric cosine in.txt")).unwrap_err();
+        assert!(err.to_string().contains("unknown metric 'cosine'"));
+        assert!(parse_args(&args("totals --metric in.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_reconcile() {
+        let parsed =
+            parse_args(&args("reconcile in.txt --output out --export json --top 3")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Reconcile(ReconcileOptions {
+                output: PathBuf::from("out"),
+                export: Export::Json,
+                top: 3,
+            })
+        );
+        assert_eq!(parsed.input, "in.txt");
+
+        let parsed = parse_args(&args("reconcile --output out in.txt")).unwrap();
+        assert!(matches!(
+            parsed.command,
+            Command::Reconcile(ReconcileOptions {
+                export: Export::Csv,
+                top: 10,
+                ..
+            })
+        ));
+
+        assert!(parse_args(&args("reconcile in.txt")).is_err());
+        assert!(parse_args(&args("reconcile --output out --export xml in.txt")).is_err());
+    }
+}
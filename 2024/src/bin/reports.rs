@@ -0,0 +1,192 @@
+//! Checks reactor reports against safety rules other than the puzzle's, and explains why each
+//! unsafe report was rejected. Reports are read line by line, see
+//! [`advent_of_code_2024::red_nosed_report::rules`].
+
+use advent_of_code_2024::red_nosed_report::parse_report;
+use advent_of_code_2024::red_nosed_report::rules::{SafetyRules, Violation};
+use aoc_common::args::{parse_number, parse_value};
+use aoc_common::error::{Error, Result};
+use aoc_common::input::open_path_or_stdin;
+use aoc_common::report::{diagnostic, Format, JsonObject};
+use std::env;
+use std::io::BufRead;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: reports [--format text|json] [--min-step <N>] [--max-step <N>] [--direction increasing|decreasing|either] [--allow-plateaus] [--explain] <input file | ->";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    rules: SafetyRules,
+    /// Whether every unsafe report is listed with the first rule it breaks.
+    explain: bool,
+    input: String,
+    format: Format,
+}
+
+fn usage_error(message: impl Into<String>) -> Error {
+    Error::Usage(message.into())
+}
+
+fn parse_args(args: &[String]) -> Result<Args> {
+    let mut rules = SafetyRules::default();
+    let mut explain = false;
+    let mut format = Format::Text;
+    let mut input = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = parse_value(iter.next(), "--format")?;
+                format = value.parse().map_err(|_| {
+                    usage_error(format!("--format must be text or json, got '{}'", value))
+                })?;
+            }
+            "--min-step" => rules.min_step = parse_number(iter.next(), "--min-step")?,
+            "--max-step" => rules.max_step = parse_number(iter.next(), "--max-step")?,
+            "--direction" => {
+                let value = parse_value(iter.next(), "--direction")?;
+                rules.direction = value.parse().map_err(|_| {
+                    usage_error(format!(
+                        "--direction must be increasing, decreasing or either, got '{}'",
+                        value
+                    ))
+                })?;
+            }
+            "--allow-plateaus" => rules.allow_plateaus = true,
+            "--explain" => explain = true,
+            flag if flag.starts_with("--") => {
+                return Err(usage_error(format!("unknown option '{}'", flag)))
+            }
+            path => {
+                if input.replace(path.to_string()).is_some() {
+                    return Err(usage_error("give only one input file"));
+                }
+            }
+        }
+    }
+    if rules.min_step > rules.max_step {
+        return Err(usage_error(format!(
+            "--min-step {} is above --max-step {}",
+            rules.min_step, rules.max_step
+        )));
+    }
+
+    Ok(Args {
+        rules,
+        explain,
+        input: input.ok_or_else(|| usage_error("missing input file"))?,
+        format,
+    })
+}
+
+/// Prints why the report on the 0-based line `index` is unsafe.
+fn explain(format: Format, index: usize, violation: &Violation) {
+    match format {
+        Format::Text => println!("line {}: {}", index + 1, violation),
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("line", index + 1)
+                .number("index", violation.index)
+                .number("from", violation.from)
+                .number("to", violation.to)
+                .string("rule", violation.rule.name())
+                .string("message", &violation.to_string())
+        ),
+    }
+}
+
+fn check(args: &Args) -> Result<()> {
+    let (mut reports, mut unsafe_reports) = (0, 0);
+    for (index, line) in open_path_or_stdin(&args.input)?.lines().enumerate() {
+        let levels = parse_report(index, &line?)?;
+        reports += 1;
+        if let Some(violation) = args.rules.first_violation(&levels) {
+            unsafe_reports += 1;
+            if args.explain {
+                explain(args.format, index, &violation);
+            }
+        }
+    }
+
+    match args.format {
+        Format::Text => {
+            println!("Reports  {}", reports);
+            println!("Safe     {}", reports - unsafe_reports);
+            println!("Unsafe   {}", unsafe_reports);
+        }
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("reports", reports)
+                .number("safe", reports - unsafe_reports)
+                .number("unsafe", unsafe_reports)
+        ),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match check(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", diagnostic(args.format, "error", &e.to_string()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::red_nosed_report::rules::Trend;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_reports_args() {
+        let parsed = parse_args(&args(
+            "--format json --min-step 0 --max-step 5 --direction decreasing --allow-plateaus --explain -",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            Args {
+                rules: SafetyRules {
+                    min_step: 0,
+                    max_step: 5,
+                    direction: Trend::Decreasing,
+                    allow_plateaus: true,
+                },
+                explain: true,
+                input: "-".to_string(),
+                format: Format::Json,
+            }
+        );
+
+        let parsed = parse_args(&args("in.txt")).unwrap();
+        assert_eq!(
+            (parsed.rules, parsed.explain),
+            (SafetyRules::default(), false)
+        );
+
+        assert!(parse_args(&args("")).is_err());
+        assert!(parse_args(&args("--min-step 4 in.txt")).is_err());
+        assert!(parse_args(&args("--direction up in.txt")).is_err());
+        assert!(parse_args(&args("--max-step -1 in.txt")).is_err());
+        assert!(parse_args(&args("--dampen in.txt")).is_err());
+    }
+}
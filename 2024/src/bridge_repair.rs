@@ -1,24 +1,76 @@
-use crate::utils::Args;
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use aoc_common::error::{parse_token, Error, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 
-fn evaluate_equation(nums: &[i64], ops: &[char]) -> i64 {
+pub struct BridgeRepair;
+
+impl Solution for BridgeRepair {
+    type Input = Vec<(i64, Vec<i64>)>;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| parse_line(index, line))
+            .collect()
+    }
+
+    fn part1(equations: &Self::Input) -> Result<Answer> {
+        Ok(solve_calibrations(equations)?.into())
+    }
+
+    fn part2(equations: &Self::Input) -> Result<Answer> {
+        Ok(solve_calibrations_part2(equations)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_equations(rng, size))
+    }
+}
+
+/// Evaluates the numbers left to right with the given operators, or `None` if a running
+/// value leaves `i64`. Such a combination can never reach an `i64` test value.
+pub fn evaluate_equation(nums: &[i64], ops: &[char]) -> Result<Option<i64>> {
     let mut result = nums[0];
     for (i, &op) in ops.iter().enumerate() {
-        match op {
-            '+' => result += nums[i + 1],
-            '*' => result *= nums[i + 1],
-            _ => panic!("Unknown operator"),
+        let next = match op {
+            '+' => result.checked_add(nums[i + 1]),
+            '*' => result.checked_mul(nums[i + 1]),
+            _ => return Err(unknown_operator(op)),
+        };
+        match next {
+            Some(next) => result = next,
+            None => return Ok(None),
         }
     }
-    result
+    Ok(Some(result))
+}
+
+fn unknown_operator(op: char) -> Error {
+    Error::InvalidInput(format!("unknown operator '{}'", op))
 }
 
-fn solve_equation(test_value: i64, nums: &[i64]) -> Option<Vec<char>> {
+/// The number of operator combinations for `nums`, with `operators` choices between each
+/// two numbers. Equations too long to try every combination are rejected.
+fn combinations(nums: &[i64], operators: u64) -> Result<u64> {
+    let num_ops = nums.len() - 1;
+    u32::try_from(num_ops)
+        .ok()
+        .and_then(|num_ops| operators.checked_pow(num_ops))
+        .ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "an equation of {} numbers has too many operator combinations to try",
+                nums.len()
+            ))
+        })
+}
+
+pub fn solve_equation(test_value: i64, nums: &[i64]) -> Result<Option<Vec<char>>> {
     let num_ops = nums.len() - 1;
 
     // Generate all possible operator combinations
-    for i in 0..(2_u32.pow(num_ops as u32)) {
+    for i in 0..combinations(nums, 2)? {
         let mut ops = Vec::new();
         let mut current_combo = i;
 
@@ -30,60 +82,114 @@ fn solve_equation(test_value: i64, nums: &[i64]) -> Option<Vec<char>> {
         }
 
         // Check if this combination works
-        let result = evaluate_equation(nums, &ops);
-        if result == test_value {
-            return Some(ops);
+        if evaluate_equation(nums, &ops)? == Some(test_value) {
+            return Ok(Some(ops));
         }
     }
 
-    None
+    Ok(None)
 }
 
-fn parse_line(line: &str) -> (i64, Vec<i64>) {
-    let parts: Vec<&str> = line.split(": ").collect();
-    let test_value: i64 = parts[0].parse().unwrap();
-    let nums: Vec<i64> = parts[1]
+/// Parses one `test_value: n1 n2 ...` equation; `index` is the 0-based line number.
+pub fn parse_line(index: usize, line: &str) -> Result<(i64, Vec<i64>)> {
+    let (test_value, nums) = line
+        .split_once(": ")
+        .ok_or_else(|| Error::malformed(index, line, line, "expected 'test value: numbers'"))?;
+
+    let test_value: i64 = parse_token(index, line, test_value, "test value")?;
+    let nums = nums
         .split_whitespace()
-        .map(|n| n.parse().unwrap())
-        .collect();
+        .map(|n| parse_token(index, line, n, "number"))
+        .collect::<Result<Vec<i64>>>()?;
+
+    if nums.is_empty() {
+        return Err(Error::malformed(
+            index,
+            line,
+            &line[line.len()..],
+            "expected at least one number",
+        ));
+    }
 
-    (test_value, nums)
+    Ok((test_value, nums))
 }
 
-fn solve_calibrations(input_path: &str) -> i64 {
-    let input = fs::read_to_string(input_path).expect("Could not read file");
+/// `size` equations of 2 to 8 numbers below 100. Most test values are made by combining the
+/// numbers with random operators, so both parts have something to find; the others are
+/// usually unreachable.
+pub fn generate_equations(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let nums: Vec<i64> = (0..rng.range(2..=8)).map(|_| rng.range(1..=99)).collect();
+        let mut test_value = nums[0];
+        for &num in &nums[1..] {
+            test_value = match rng.range(0..=2) {
+                0 => test_value + num,
+                1 => test_value * num,
+                _ => concatenate(test_value, num).expect("8 numbers below 100 fit in i64"),
+            };
+        }
+        if rng.ratio(1, 4) {
+            test_value += rng.range(1..=9);
+        }
+
+        let nums: Vec<String> = nums.iter().map(i64::to_string).collect();
+        input.push_str(&format!("{}: {}\n", test_value, nums.join(" ")));
+    }
     input
-        .lines()
-        .filter_map(|line| {
-            let (test_value, nums) = parse_line(line);
-            solve_equation(test_value, &nums).map(|_| test_value)
-        })
-        .sum()
 }
 
-fn concatenate(a: i64, b: i64) -> i64 {
+pub fn solve_calibrations(equations: &[(i64, Vec<i64>)]) -> Result<i64> {
+    sum_solvable(equations, solve_equation)
+}
+
+/// Finds the operators that make the numbers evaluate to the test value, if any.
+type Solver = fn(i64, &[i64]) -> Result<Option<Vec<char>>>;
+
+/// The sum of the test values of the equations that `solve` finds operators for.
+fn sum_solvable(equations: &[(i64, Vec<i64>)], solve: Solver) -> Result<i64> {
+    let mut sum: i64 = 0;
+    for (test_value, nums) in equations {
+        if solve(*test_value, nums)?.is_some() {
+            sum = sum.checked_add(*test_value).ok_or_else(|| {
+                Error::InvalidInput("the total calibration result does not fit in 64 bits".into())
+            })?;
+        }
+    }
+    Ok(sum)
+}
+
+/// `a` followed by the digits of `b`, or `None` if that does not fit in `i64`.
+pub fn concatenate(a: i64, b: i64) -> Option<i64> {
     let b_digits = b.to_string().len();
-    a * 10_i64.pow(b_digits as u32) + b
+    10_i64
+        .checked_pow(b_digits as u32)
+        .and_then(|shift| a.checked_mul(shift))
+        .and_then(|shifted| shifted.checked_add(b))
 }
 
-fn evaluate_equation_part2(nums: &[i64], ops: &[char]) -> i64 {
+pub fn evaluate_equation_part2(nums: &[i64], ops: &[char]) -> Result<Option<i64>> {
     let mut result = nums[0];
     for (i, &op) in ops.iter().enumerate() {
-        match op {
-            '+' => result += nums[i + 1],
-            '*' => result *= nums[i + 1],
-            '|' => result = concatenate(result, nums[i + 1]),
-            _ => panic!("Unknown operator"),
+        let next = match op {
+            '+' => result.checked_add(nums[i + 1]),
+            '*' => result.checked_mul(nums[i + 1]),
+            '|' => concatenate(result, nums[i + 1]),
+            _ => return Err(unknown_operator(op)),
+        };
+        match next {
+            Some(next) => result = next,
+            None => return Ok(None),
         }
     }
-    result
+    Ok(Some(result))
 }
 
-fn solve_equation_part2(test_value: i64, nums: &[i64]) -> Option<Vec<char>> {
+pub fn solve_equation_part2(test_value: i64, nums: &[i64]) -> Result<Option<Vec<char>>> {
     let num_ops = nums.len() - 1;
 
     // Generate all possible operator combinations (now including ||)
-    for i in 0..(3_u32.pow(num_ops as u32)) {
+    for i in 0..combinations(nums, 3)? {
         let mut ops = Vec::new();
         let mut current_combo = i;
 
@@ -100,51 +206,85 @@ fn solve_equation_part2(test_value: i64, nums: &[i64]) -> Option<Vec<char>> {
         }
 
         // Check if this combination works
-        let result = evaluate_equation_part2(nums, &ops);
-        if result == test_value {
-            return Some(ops);
+        if evaluate_equation_part2(nums, &ops)? == Some(test_value) {
+            return Ok(Some(ops));
         }
     }
 
-    None
-}
-
-fn solve_calibrations_part2(input_path: &str) -> i64 {
-    let input = fs::read_to_string(input_path).expect("Could not read file");
-    input
-        .lines()
-        .filter_map(|line| {
-            let (test_value, nums) = parse_line(line);
-            solve_equation_part2(test_value, &nums).map(|_| test_value)
-        })
-        .sum()
+    Ok(None)
 }
 
-pub fn run_part1() {
-    let args = Args::parse();
-    let result = solve_calibrations(&args.file_path);
-    println!("Part 1 - Calibration Result: {}", result);
-}
-
-pub fn run_part2() {
-    let args = Args::parse();
-    let result = solve_calibrations_part2(&args.file_path);
-    println!("Part 2 - Calibration Result: {}", result);
+pub fn solve_calibrations_part2(equations: &[(i64, Vec<i64>)]) -> Result<i64> {
+    sum_solvable(equations, solve_equation_part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn read_equations(input_path: &str) -> Vec<(i64, Vec<i64>)> {
+        BridgeRepair::parse_file(input_path).unwrap()
+    }
+
     #[test]
     fn test_solve_calibrations() {
-        let result = solve_calibrations("input/7-bridge-repair/test-input-1.txt");
+        let result =
+            solve_calibrations(&read_equations("input/7-bridge-repair/test-input-1.txt")).unwrap();
         assert_eq!(result, 3749);
     }
 
     #[test]
     fn test_solve_calibrations_part2() {
-        let result = solve_calibrations_part2("input/7-bridge-repair/test-input-2.txt");
+        let result =
+            solve_calibrations_part2(&read_equations("input/7-bridge-repair/test-input-2.txt"))
+                .unwrap();
         assert_eq!(result, 11387);
     }
+
+    #[test]
+    fn test_parse_line_errors() {
+        let err = parse_line(0, "190 10 19").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::MalformedLine {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+
+        let err = parse_line(4, "190: 10 1x").unwrap_err();
+        assert_eq!(err.to_string(), "line 5, column 9: invalid number '1x'");
+
+        let err = parse_line(0, "190: ").unwrap_err();
+        assert!(matches!(err, Error::MalformedLine { column: 6, .. }));
+    }
+
+    #[test]
+    fn test_overflow_and_long_equations() {
+        // 2^40 * 2^40 leaves i64; adding instead reaches the test value.
+        let big = 1 << 40;
+        assert_eq!(
+            solve_calibrations(&[(2 * big, vec![big, big])]).unwrap(),
+            2 * big
+        );
+        assert_eq!(concatenate(i64::MAX / 10, 99), None);
+        assert_eq!(
+            solve_calibrations_part2(&[(5, vec![i64::MAX / 10, 99])]).unwrap(),
+            0
+        );
+        assert_eq!(
+            solve_calibrations(&[(i64::MAX, vec![i64::MAX]), (1, vec![1])])
+                .unwrap_err()
+                .to_string(),
+            "invalid input: the total calibration result does not fit in 64 bits"
+        );
+
+        let long = vec![1; 66];
+        assert_eq!(
+            solve_equation(1, &long).unwrap_err().to_string(),
+            "invalid input: an equation of 66 numbers has too many operator combinations to try"
+        );
+        assert!(evaluate_equation(&[1, 2], &['-']).is_err());
+    }
 }
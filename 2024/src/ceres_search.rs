@@ -1,87 +1,78 @@
-use crate::utils::Args;
-use regex::Regex;
-use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use aoc_common::direction::{Compass, Heading};
+use aoc_common::error::Result;
+use aoc_common::grid::{Grid, Pos};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 
-pub fn run_part1() {
-    let args = Args::parse();
-    let word = "XMAS";
-    let count = find_word(&args.file_path, word);
-    println!("The word '{}' appears {} times in the grid.", word, count);
-}
-
-fn read_grid(file_path: &str) -> Vec<Vec<char>> {
-    let content = fs::read_to_string(file_path).expect("Failed to read the input file");
-
-    content.lines().map(|line| line.chars().collect()).collect()
-}
+pub struct CeresSearch;
 
-fn find_word(file_path: &str, word: &str) -> usize {
-    let grid = read_grid(file_path);
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let word_len = word.len();
-    let word_chars: Vec<char> = word.chars().collect();
+impl Solution for CeresSearch {
+    type Input = Grid<char>;
 
-    let mut count = 0;
-
-    // Directions for traversal: right, left, down, up, diagonals
-    let directions = vec![
-        (0, 1),   // right
-        (0, -1),  // left
-        (1, 0),   // down
-        (-1, 0),  // up
-        (1, 1),   // diagonal down-right
-        (1, -1),  // diagonal down-left
-        (-1, 1),  // diagonal up-right
-        (-1, -1), // diagonal up-left
-    ];
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_grid(input)
+    }
 
-    // Iterate over every cell in the grid
-    for row in 0..rows {
-        for col in 0..cols {
-            // Check all directions
-            for &(dr, dc) in &directions {
-                let mut match_count = 0;
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        Ok(find_word(grid, "XMAS").into())
+    }
 
-                // Check if the word fits in the current direction
-                for k in 0..word_len {
-                    let nr = row as isize + k as isize * dr;
-                    let nc = col as isize + k as isize * dc;
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_grid(rng, size))
+    }
+}
 
-                    if nr < 0 || nr >= rows as isize || nc < 0 || nc >= cols as isize {
-                        break;
-                    }
+/// Reads the letter grid; every row must be as wide as the first one.
+pub fn read_grid(input: &str) -> Result<Grid<char>> {
+    Grid::parse(input)
+}
 
-                    if grid[nr as usize][nc as usize] == word_chars[k] {
-                        match_count += 1;
-                    } else {
-                        break;
-                    }
-                }
+/// A `size` by `size` grid of the letters X, M, A and S.
+pub fn generate_grid(rng: &mut Rng, size: usize) -> String {
+    let grid = Grid::new(size, size, 'X').map(|_| *rng.pick(&['X', 'M', 'A', 'S']));
+    format!("{}\n", grid)
+}
 
-                if match_count == word_len {
-                    count += 1;
-                }
-            }
-        }
-    }
+/// Counts the occurrences of `word` in all eight directions, overlaps included.
+pub fn find_word(grid: &Grid<char>, word: &str) -> usize {
+    grid.positions()
+        .map(|start| {
+            Compass::ALL
+                .into_iter()
+                .filter(|&direction| spells(grid, start, direction, word))
+                .count()
+        })
+        .sum()
+}
 
-    count
+/// Whether `word` is spelled from `start` in the given direction.
+fn spells(grid: &Grid<char>, start: Pos, direction: Compass, word: &str) -> bool {
+    let (d_row, d_col) = direction.delta();
+    let mut letters = grid.ray(start, d_row, d_col).map(|pos| grid[pos]);
+    word.chars()
+        .all(|expected| letters.next() == Some(expected))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_find_word() {
         let input_path = "input/4-ceres-search/test-input-1.txt";
         let word = "XMAS";
         let expected_count = 18;
-        let result = find_word(input_path, word);
+        let input = fs::read_to_string(input_path).expect("Failed to read the input file");
+        let result = find_word(&read_grid(&input).unwrap(), word);
 
         assert_eq!(result, expected_count);
     }
+
+    #[test]
+    fn test_find_word_at_the_edges() {
+        let grid = read_grid("XMAS\nMM..\nA.A.\nS..S\n").unwrap();
+        assert_eq!(find_word(&grid, "XMAS"), 3);
+        assert!(read_grid("XMAS\nXM\n").is_err());
+    }
 }
@@ -1,78 +1,94 @@
-use crate::utils::Args;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use aoc_common::error::{Error, Result};
+use aoc_common::parse::{self, Line};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 
-#[derive(Debug)]
-struct ClawMachine {
-    button_a: (i64, i64), // (X movement, Y movement)
-    button_b: (i64, i64),
-    prize: (i64, i64), // Prize location (X, Y)
+pub struct ClawContraption;
+
+impl Solution for ClawContraption {
+    type Input = Vec<ClawMachine>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(machines: &Self::Input) -> Result<Answer> {
+        Ok(solve(machines, 100).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_machines(rng, size))
+    }
 }
 
-enum PuzzlePart {
-    Part1,
-    Part2,
+#[derive(Debug)]
+pub struct ClawMachine {
+    pub button_a: (i64, i64), // (X movement, Y movement)
+    pub button_b: (i64, i64),
+    pub prize: (i64, i64), // Prize location (X, Y)
 }
 
-fn parse_input(input_path: &str) -> Vec<ClawMachine> {
-    let file = File::open(input_path).expect("Failed to open input file");
-    let reader = BufReader::new(file);
-    let mut machines = Vec::new();
-    let mut current_machine = None;
-
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if line.trim().is_empty() {
-            if let Some(machine) = current_machine.take() {
-                machines.push(machine);
-            }
-            continue;
-        }
+/// Parses blank-line separated machines, each made of a `Button A`, `Button B` and `Prize` line.
+pub fn parse_input(input: &str) -> Result<Vec<ClawMachine>> {
+    parse::sections(input)
+        .iter()
+        .map(|section| parse_machine(section))
+        .collect()
+}
 
-        if line.starts_with("Button A:") {
-            let parts: Vec<&str> = line.split(", ").collect();
-            let x = parts[0]
-                .trim_start_matches("Button A: X")
-                .parse::<i64>()
-                .unwrap();
-            let y = parts[1].trim_start_matches("Y").parse::<i64>().unwrap();
-            current_machine = Some(ClawMachine {
-                button_a: (x, y),
-                button_b: (0, 0),
-                prize: (0, 0),
-            });
-        } else if line.starts_with("Button B:") {
-            if let Some(ref mut machine) = current_machine {
-                let parts: Vec<&str> = line.split(", ").collect();
-                let x = parts[0]
-                    .trim_start_matches("Button B: X")
-                    .parse::<i64>()
-                    .unwrap();
-                let y = parts[1].trim_start_matches("Y").parse::<i64>().unwrap();
-                machine.button_b = (x, y);
-            }
-        } else if line.starts_with("Prize:") {
-            if let Some(ref mut machine) = current_machine {
-                let parts: Vec<&str> = line.split(", ").collect();
-                let x = parts[0]
-                    .trim_start_matches("Prize: X=")
-                    .parse::<i64>()
-                    .unwrap();
-                let y = parts[1].trim_start_matches("Y=").parse::<i64>().unwrap();
-                machine.prize = (x, y);
-            }
-        }
+/// `size` machines with two-digit buttons. Three prizes in four can be won within 100 presses
+/// of each button; the others are placed at random.
+pub fn generate_machines(rng: &mut Rng, size: usize) -> String {
+    let mut machines = Vec::with_capacity(size);
+    for _ in 0..size {
+        let button_a = (rng.range(10..=99), rng.range(10..=99));
+        let button_b = (rng.range(10..=99), rng.range(10..=99));
+        let prize = if rng.ratio(3, 4) {
+            let (a, b) = (rng.range(0..=100), rng.range(0..=100));
+            (
+                a * button_a.0 + b * button_b.0,
+                a * button_a.1 + b * button_b.1,
+            )
+        } else {
+            (rng.range(1_000..=20_000), rng.range(1_000..=20_000))
+        };
+        machines.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            button_a.0, button_a.1, button_b.0, button_b.1, prize.0, prize.1
+        ));
     }
+    machines.join("\n")
+}
 
-    if let Some(machine) = current_machine {
-        machines.push(machine);
+fn parse_machine(section: &[Line]) -> Result<ClawMachine> {
+    let mut button_a = None;
+    let mut button_b = None;
+    let mut prize = None;
+
+    for line in section {
+        let slot = if line.text.starts_with("Button A: ") {
+            &mut button_a
+        } else if line.text.starts_with("Button B: ") {
+            &mut button_b
+        } else if line.text.starts_with("Prize: ") {
+            &mut prize
+        } else {
+            return Err(line.error(line.text, "expected 'Button A:', 'Button B:' or 'Prize:'"));
+        };
+        let [x, y] = line.integers_n("coordinate")?;
+        *slot = Some((x, y));
     }
 
-    machines
+    Ok(ClawMachine {
+        button_a: button_a.ok_or(Error::MissingSection("'Button A' line of a claw machine"))?,
+        button_b: button_b.ok_or(Error::MissingSection("'Button B' line of a claw machine"))?,
+        prize: prize.ok_or(Error::MissingSection("'Prize' line of a claw machine"))?,
+    })
 }
 
-fn find_solution(machine: &ClawMachine, max_presses: i64) -> Option<(i64, i64)> {
+/// Reference solver: tries every combination of presses and returns the first that reaches
+/// the prize, i.e. the one with the fewest `A` presses.
+pub fn find_solution(machine: &ClawMachine, max_presses: i64) -> Option<(i64, i64)> {
     // Try all combinations of button presses up to max_presses
     for a in 0..=max_presses {
         for b in 0..=max_presses {
@@ -87,50 +103,65 @@ fn find_solution(machine: &ClawMachine, max_presses: i64) -> Option<(i64, i64)>
     None
 }
 
-fn calculate_tokens(a_presses: i64, b_presses: i64) -> i64 {
-    a_presses * 3 + b_presses * 1
+/// Solves `a * A + b * B = prize` directly with Cramer's rule. When the buttons move in the
+/// same direction there may be several solutions, so every count of `A` presses is tried
+/// instead and, like [`find_solution`], the one with the fewest `A` presses is returned.
+pub fn find_solution_linear(machine: &ClawMachine, max_presses: i64) -> Option<(i64, i64)> {
+    let (ax, ay) = machine.button_a;
+    let (bx, by) = machine.button_b;
+    let (px, py) = machine.prize;
+    let presses = 0..=max_presses;
+
+    let determinant = ax * by - ay * bx;
+    if determinant != 0 {
+        let a = px * by - py * bx;
+        let b = ax * py - ay * px;
+        if a % determinant != 0 || b % determinant != 0 {
+            return None;
+        }
+        let (a, b) = (a / determinant, b / determinant);
+        return (presses.contains(&a) && presses.contains(&b)).then_some((a, b));
+    }
+
+    presses.clone().find_map(|a| {
+        let (rest_x, rest_y) = (px - a * ax, py - a * ay);
+        let b = match (bx, by) {
+            (0, 0) => 0,
+            (0, _) => rest_y / by,
+            _ => rest_x / bx,
+        };
+        (presses.contains(&b) && b * bx == rest_x && b * by == rest_y).then_some((a, b))
+    })
+}
+
+pub fn calculate_tokens(a_presses: i64, b_presses: i64) -> i64 {
+    a_presses * 3 + b_presses
 }
 
-fn solve(machines: &[ClawMachine], max_presses: i64) -> i64 {
+pub fn solve(machines: &[ClawMachine], max_presses: i64) -> i64 {
     let mut total_tokens = 0;
-    let mut winnable_prizes = 0;
 
     for machine in machines {
-        if let Some((a_presses, b_presses)) = find_solution(machine, max_presses) {
+        if let Some((a_presses, b_presses)) = find_solution_linear(machine, max_presses) {
             total_tokens += calculate_tokens(a_presses, b_presses);
-            winnable_prizes += 1;
         }
     }
 
-    println!("Winnable prizes: {}", winnable_prizes);
     total_tokens
 }
 
-pub fn run(part: PuzzlePart, input_path: &str) -> i64 {
-    let machines = parse_input(input_path);
-    match part {
-        PuzzlePart::Part1 => solve(&machines, 100),
-        PuzzlePart::Part2 => 0, // Part 2 not implemented yet
-    }
-}
-
-pub fn run_part1() {
-    let args = Args::parse();
-    let result = run(PuzzlePart::Part1, &args.file_path);
-    println!("Total tokens needed: {}", result);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_part1() {
         // Run the solution
-        let result = run(
-            PuzzlePart::Part1,
-            "input/13-claw-contraption/test-input-1.txt",
-        );
+        let input = fs::read_to_string("input/13-claw-contraption/test-input-1.txt")
+            .expect("Failed to open input file");
+        let machines = parse_input(&input).unwrap();
+        let result = solve(&machines, 100);
 
         // According to the puzzle description:
         // - First machine costs 280 tokens (80 A presses * 3 + 40 B presses * 1)
@@ -140,4 +171,26 @@ mod tests {
         // Total: 480 tokens
         assert_eq!(result, 480);
     }
+
+    #[test]
+    fn test_parse_input_errors() {
+        let err = parse_input("Button A: X+94, Y+3x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 19: invalid coordinate '3x'"
+        );
+
+        let err = parse_input("Button A: X+94, Y+34\nPrize: X=8400, Y=5400\n").unwrap_err();
+        assert!(matches!(err, Error::MissingSection(_)));
+
+        let err = parse_input("Button C: X+1, Y+1\n").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::MalformedLine {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+    }
 }
@@ -1,34 +1,68 @@
-use crate::utils::Args;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use aoc_common::error::{Error, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 
-pub fn run_part1() {
-    let args = Args::parse();
-    match compact_disk(&args.file_path) {
-        Ok((_, checksum)) => println!("Part 1: {}", checksum),
-        Err(e) => eprintln!("Error: {}", e),
+pub struct DiskFragmentation;
+
+impl Solution for DiskFragmentation {
+    type Input = String;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(disk_map: &Self::Input) -> Result<Answer> {
+        let (_, checksum) = compact_disk_two_pointer(disk_map)?;
+        Ok(checksum.into())
+    }
+
+    fn part2(disk_map: &Self::Input) -> Result<Answer> {
+        let (_, checksum) = compact_disk_part2(disk_map)?;
+        Ok(checksum.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_disk_map(rng, size))
     }
 }
 
-pub fn compact_disk(file_path: &str) -> Result<(String, u128), std::io::Error> {
-    let disk_map = read_input_from_file(file_path)?;
-    if disk_map.is_empty() || !disk_map.chars().all(|c| c.is_ascii_digit()) {
-        panic!("Input contains non-digit characters or is empty!");
+/// Parses the disk map into alternating file and free space lengths.
+fn parse_lengths(disk_map: &str) -> Result<Vec<usize>> {
+    if disk_map.is_empty() {
+        return Err(Error::MissingSection("disk map"));
     }
 
-    for (i, c) in disk_map.chars().enumerate() {
-        if c.to_digit(10).is_none() {
-            println!("Non-digit character at position {}: {:?}", i, c);
-            panic!("Invalid input character");
+    let mut lengths = Vec::with_capacity(disk_map.len());
+    for (line_index, line) in disk_map.lines().enumerate() {
+        for (i, c) in line.chars().enumerate() {
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| Error::invalid_character(line_index, i, c))?;
+            lengths.push(digit as usize);
         }
     }
 
-    // Parse the input into alternating file and free space lengths
-    let lengths: Vec<usize> = disk_map
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize)
+    Ok(lengths)
+}
+
+/// A disk map of `size` digits: files of 1 to 9 blocks alternating with free spans of 0 to 9.
+pub fn generate_disk_map(rng: &mut Rng, size: usize) -> String {
+    let mut disk_map: String = (0..size)
+        .map(|index| {
+            let low = if index % 2 == 0 { 1 } else { 0 };
+            char::from(b'0' + rng.range(low..=9) as u8)
+        })
         .collect();
+    disk_map.push('\n');
+    disk_map
+}
+
+/// Reference solver for part 1: repeatedly moves the last file block into the first free
+/// block, scanning the whole disk for both on every move.
+pub fn compact_disk(disk_map: &str) -> Result<(String, u128)> {
+    // Parse the input into alternating file and free space lengths
+    let lengths = parse_lengths(disk_map)?;
 
     // Create the initial block representation
     let mut blocks: Vec<Option<usize>> = Vec::new();
@@ -36,9 +70,10 @@ pub fn compact_disk(file_path: &str) -> Result<(String, u128), std::io::Error> {
 
     for (i, &length) in lengths.iter().enumerate() {
         let is_file = i % 2 == 0;
-        blocks.extend(
-            std::iter::repeat(if is_file { Some(current_file_id) } else { None }).take(length),
-        );
+        blocks.extend(std::iter::repeat_n(
+            if is_file { Some(current_file_id) } else { None },
+            length,
+        ));
         if is_file {
             current_file_id += 1;
         }
@@ -58,7 +93,7 @@ pub fn compact_disk(file_path: &str) -> Result<(String, u128), std::io::Error> {
                 && blocks
                     .iter()
                     .position(|&x| x.is_none())
-                    .map_or(true, |fpos| fpos < blocks.len())
+                    .is_none_or(|fpos| fpos < blocks.len())
         });
         if let Some(rf) = rightmost_file {
             if rf <= leftmost_free {
@@ -87,34 +122,53 @@ pub fn compact_disk(file_path: &str) -> Result<(String, u128), std::io::Error> {
     Ok((block_string, checksum))
 }
 
-pub fn run_part2() {
-    let args = Args::parse();
-    match compact_disk_part2(&args.file_path) {
-        Ok((_, checksum)) => println!("Part 2: {}", checksum),
-        Err(e) => eprintln!("Error: {}", e),
+/// Compacts like [`compact_disk`] in a single pass: one index walks forward over free
+/// blocks, the other backward over file blocks, and blocks are swapped until they meet.
+pub fn compact_disk_two_pointer(disk_map: &str) -> Result<(String, u128)> {
+    let lengths = parse_lengths(disk_map)?;
+    let mut blocks = Vec::with_capacity(lengths.iter().sum());
+    for (i, &length) in lengths.iter().enumerate() {
+        let block = (i % 2 == 0).then_some(i / 2);
+        blocks.extend(std::iter::repeat_n(block, length));
     }
-}
 
-pub fn compact_disk_part2(file_path: &str) -> Result<(String, u128), std::io::Error> {
-    let disk_map = read_input_from_file(file_path)?;
-    if disk_map.is_empty() || !disk_map.chars().all(|c| c.is_ascii_digit()) {
-        panic!("Input contains non-digit characters or is empty!");
+    let (mut free, mut file) = (0, blocks.len());
+    loop {
+        while free < blocks.len() && blocks[free].is_some() {
+            free += 1;
+        }
+        while file > 0 && blocks[file - 1].is_none() {
+            file -= 1;
+        }
+        if file == 0 || free >= file - 1 {
+            break;
+        }
+        blocks.swap(free, file - 1);
     }
 
-    // Parse the input
-    let lengths: Vec<usize> = disk_map
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize)
+    let block_string = blocks
+        .iter()
+        .map(|&block| match block {
+            None => '.',
+            Some(id) => id.to_string().chars().next().unwrap(),
+        })
         .collect();
+    Ok((block_string, calculate_checksum(&blocks)))
+}
+
+pub fn compact_disk_part2(disk_map: &str) -> Result<(String, u128)> {
+    // Parse the input
+    let lengths = parse_lengths(disk_map)?;
 
     let mut blocks: Vec<Option<usize>> = Vec::new();
     let mut current_file_id = 0;
 
     for (i, &length) in lengths.iter().enumerate() {
         let is_file = i % 2 == 0;
-        blocks.extend(
-            std::iter::repeat(if is_file { Some(current_file_id) } else { None }).take(length),
-        );
+        blocks.extend(std::iter::repeat_n(
+            if is_file { Some(current_file_id) } else { None },
+            length,
+        ));
         if is_file {
             current_file_id += 1;
         }
@@ -150,9 +204,7 @@ pub fn compact_disk_part2(file_path: &str) -> Result<(String, u128), std::io::Er
             }
 
             // Fill the new position
-            for pos in span.start..(span.start + file_len) {
-                blocks[pos] = Some(file_id);
-            }
+            blocks[span.start..span.start + file_len].fill(Some(file_id));
         }
     }
 
@@ -177,8 +229,8 @@ fn find_free_spans(
     let mut spans = Vec::new();
     let mut current_start = None;
 
-    for pos in start_limit..end_limit {
-        if blocks[pos].is_none() {
+    for (pos, block) in blocks.iter().enumerate().take(end_limit).skip(start_limit) {
+        if block.is_none() {
             if current_start.is_none() {
                 current_start = Some(pos);
             }
@@ -198,7 +250,7 @@ fn find_free_spans(
     spans
 }
 
-fn calculate_checksum(blocks: &[Option<usize>]) -> u128 {
+pub fn calculate_checksum(blocks: &[Option<usize>]) -> u128 {
     blocks
         .iter()
         .enumerate()
@@ -214,29 +266,36 @@ fn calculate_checksum(blocks: &[Option<usize>]) -> u128 {
         .sum()
 }
 
-pub fn read_input_from_file(filepath: &str) -> Result<String, std::io::Error> {
-    let path = Path::new(filepath);
-    let mut file = File::open(&path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents.trim().to_string())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_compact_disk() {
         let file_path = "input/9-disk-fragmentation/test-input-1.txt";
-        let (blocks, checksum) = compact_disk(file_path).expect("Failed to compact disk");
+        let disk_map = fs::read_to_string(file_path).expect("Failed to read disk map");
+        let (_blocks, checksum) = compact_disk(disk_map.trim()).expect("Failed to compact disk");
         assert_eq!(checksum, 1928);
     }
 
     #[test]
     fn test_compact_disk_part2() {
         let file_path = "input/9-disk-fragmentation/test-input-2.txt";
-        let (blocks, checksum) = compact_disk_part2(file_path).expect("Failed to compact disk");
+        let disk_map = fs::read_to_string(file_path).expect("Failed to read disk map");
+        let (_blocks, checksum) =
+            compact_disk_part2(disk_map.trim()).expect("Failed to compact disk");
         assert_eq!(checksum, 2858);
     }
+
+    #[test]
+    fn test_compact_disk_rejects_invalid_input() {
+        let err = compact_disk("12a45").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 3: invalid character 'a'");
+
+        assert!(matches!(
+            compact_disk_part2(""),
+            Err(Error::MissingSection("disk map"))
+        ));
+    }
 }
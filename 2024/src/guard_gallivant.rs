@@ -1,155 +1,134 @@
-use crate::utils::Args;
-use std::collections::{HashMap, HashSet};
-use std::fs;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
+use aoc_common::direction::Direction;
+use aoc_common::error::{Error, Result};
+use aoc_common::grid::{Grid, Pos};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
+use std::collections::HashSet;
 
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
-        }
+pub struct GuardGallivant;
+
+impl Solution for GuardGallivant {
+    type Input = Grid<char>;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_map(input)
+    }
+
+    fn part1(map: &Self::Input) -> Result<Answer> {
+        Ok(simulate_guard_path(map)?.into())
+    }
+
+    fn part2(map: &Self::Input) -> Result<Answer> {
+        Ok(patrol_routes(map)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_map(rng, size))
     }
 }
 
-fn simulate_guard_path(file_path: &str) -> usize {
-    let map_str = fs::read_to_string(file_path).expect("Could not read input file");
-    let original_map: Vec<Vec<char>> = map_str.lines().map(|line| line.chars().collect()).collect();
+/// Reads the lab map. Only `.`, `#` and the guard markers `^>v<` are allowed,
+/// and every row must be as wide as the first one.
+pub fn parse_map(input: &str) -> Result<Grid<char>> {
+    Grid::parse_with(input, |c| ".#^>v<".contains(c).then_some(c))
+}
 
-    let (guard_row, guard_col, direction) = find_initial_guard_state(&original_map);
+/// A `size` by `size` map where about one tile in ten is an obstacle, with the guard on a
+/// random tile facing a random direction.
+pub fn generate_map(rng: &mut Rng, size: usize) -> String {
+    let mut map = Grid::new(size, size, '.').map(|_| if rng.ratio(1, 10) { '#' } else { '.' });
+    let guard = Pos::new(rng.index(size), rng.index(size));
+    map[guard] = *rng.pick(&['^', '>', 'v', '<']);
+    format!("{}\n", map)
+}
+
+pub fn simulate_guard_path(original_map: &Grid<char>) -> Result<usize> {
+    let (guard, direction) = find_initial_guard_state(original_map)?;
     let mut map = original_map.clone();
 
     // Remove initial guard marker
-    map[guard_row][guard_col] = '.';
+    map[guard] = '.';
 
-    let visited_positions = run_simulation(&map, guard_row, guard_col, direction);
+    let visited_positions = run_simulation(&map, guard, direction);
 
-    visited_positions.len()
+    Ok(visited_positions.len())
 }
 
-fn run_simulation(
-    map: &Vec<Vec<char>>,
-    start_row: usize,
-    start_col: usize,
-    start_dir: Direction,
-) -> HashSet<(usize, usize)> {
+fn run_simulation(map: &Grid<char>, start: Pos, start_dir: Direction) -> HashSet<Pos> {
     let mut visited_positions = HashSet::new();
-    visited_positions.insert((start_row, start_col));
+    visited_positions.insert(start);
 
-    let mut guard_row = start_row;
-    let mut guard_col = start_col;
+    let mut guard = start;
     let mut direction = start_dir;
 
     // A safety limit to prevent infinite loops if something is off.
     // This is just a large number to prevent accidental infinite loops in this simulation
-    let max_steps = map.len() * map[0].len() * 4;
+    let max_steps = map.width() * map.height() * 4;
     let mut steps = 0;
 
     while steps < max_steps {
         steps += 1;
 
-        let (next_row, next_col) = match direction {
-            Direction::North => (guard_row.wrapping_sub(1), guard_col),
-            Direction::East => (guard_row, guard_col + 1),
-            Direction::South => (guard_row + 1, guard_col),
-            Direction::West => (guard_row, guard_col.wrapping_sub(1)),
-        };
-
-        // Check out-of-bounds
-        if next_row >= map.len() || next_col >= map[0].len() {
+        let Some(next) = map.step(guard, direction) else {
             // Guard leaves the map
             break;
-        }
+        };
 
         // Check if blocked
-        if map[next_row][next_col] == '#' {
+        if map[next] == '#' {
             // Turn right
             direction = direction.turn_right();
         } else {
             // Move forward
-            guard_row = next_row;
-            guard_col = next_col;
-            visited_positions.insert((guard_row, guard_col));
+            guard = next;
+            visited_positions.insert(guard);
         }
     }
 
     visited_positions
 }
 
-fn find_initial_guard_state(map: &[Vec<char>]) -> (usize, usize, Direction) {
-    for (row, line) in map.iter().enumerate() {
-        for (col, &cell) in line.iter().enumerate() {
-            match cell {
-                '^' => return (row, col, Direction::North),
-                '>' => return (row, col, Direction::East),
-                'v' => return (row, col, Direction::South),
-                '<' => return (row, col, Direction::West),
-                _ => {}
-            }
-        }
-    }
-    panic!("No guard found in the map")
+fn find_initial_guard_state(map: &Grid<char>) -> Result<(Pos, Direction)> {
+    map.iter()
+        .find_map(|(pos, &cell)| Direction::from_char(cell).map(|direction| (pos, direction)))
+        .ok_or(Error::MissingSection("guard marker ('^', '>', 'v' or '<')"))
 }
 
 /// Runs a simulation with a single added obstruction and checks for loops.
 /// Returns true if adding the obstruction at `obstacle_pos` causes a loop.
-fn causes_loop(
-    mut map: Vec<Vec<char>>,
-    start_row: usize,
-    start_col: usize,
-    start_dir: Direction,
-    obstacle_pos: (usize, usize),
-) -> bool {
+fn causes_loop(mut map: Grid<char>, start: Pos, start_dir: Direction, obstacle_pos: Pos) -> bool {
     // Place the obstruction
-    map[obstacle_pos.0][obstacle_pos.1] = '#';
+    map[obstacle_pos] = '#';
 
-    let mut guard_row = start_row;
-    let mut guard_col = start_col;
+    let mut guard = start;
     let mut direction = start_dir;
 
-    // Track visited states of (row, col, direction)
+    // Track visited states of (position, direction)
     // If we ever see a state twice, we have a loop
     let mut visited_states = HashSet::new();
-    visited_states.insert((guard_row, guard_col, direction));
+    visited_states.insert((guard, direction));
 
-    let max_steps = map.len() * map[0].len() * 10; // Arbitrary large number
+    let max_steps = map.width() * map.height() * 10; // Arbitrary large number
     let mut steps = 0;
 
     while steps < max_steps {
         steps += 1;
 
-        let (next_row, next_col) = match direction {
-            Direction::North => (guard_row.wrapping_sub(1), guard_col),
-            Direction::East => (guard_row, guard_col + 1),
-            Direction::South => (guard_row + 1, guard_col),
-            Direction::West => (guard_row, guard_col.wrapping_sub(1)),
-        };
-
-        // Check out-of-bounds
-        if next_row >= map.len() || next_col >= map[0].len() {
+        let Some(next) = map.step(guard, direction) else {
             // Guard leaves the map, no loop
             return false;
-        }
+        };
 
-        if map[next_row][next_col] == '#' {
+        if map[next] == '#' {
             // Turn right
             direction = direction.turn_right();
         } else {
             // Move forward
-            guard_row = next_row;
-            guard_col = next_col;
+            guard = next;
         }
 
-        let state = (guard_row, guard_col, direction);
+        let state = (guard, direction);
         if visited_states.contains(&state) {
             // Found a loop
             return true;
@@ -164,59 +143,55 @@ fn causes_loop(
     false
 }
 
-pub fn run_part1() {
-    let args = Args::parse();
-    let visited_positions = simulate_guard_path(&args.file_path);
-    println!("Part 1 - {}", visited_positions);
-}
-
-fn patrol_routes(file_path: &str) -> usize {
-    let map_str = fs::read_to_string(file_path).expect("Could not read input file");
-    let original_map: Vec<Vec<char>> = map_str.lines().map(|line| line.chars().collect()).collect();
-
-    let (guard_row, guard_col, guard_dir) = find_initial_guard_state(&original_map);
+pub fn patrol_routes(original_map: &Grid<char>) -> Result<usize> {
+    let (guard, guard_dir) = find_initial_guard_state(original_map)?;
     let mut map = original_map.clone();
     // Remove guard marker
-    map[guard_row][guard_col] = '.';
-
-    let mut possible_positions = 0;
+    map[guard] = '.';
 
     // We must not place obstruction at the guard's starting position
-    // The guard start position is (guard_row, guard_col)
-
-    for r in 0..map.len() {
-        for c in 0..map[0].len() {
-            // Check if this position is a free space and not the guard's start
-            if (r, c) != (guard_row, guard_col) && map[r][c] == '.' {
-                // Try placing an obstruction and see if it causes a loop
-                if causes_loop(map.clone(), guard_row, guard_col, guard_dir, (r, c)) {
-                    possible_positions += 1;
-                }
-            }
-        }
-    }
-    possible_positions
-}
-
-pub fn run_part2() {
-    let args = Args::parse();
-    let possible_positions = patrol_routes(&args.file_path);
-    println!("Part 2 - {}", possible_positions);
+    let possible_positions = map
+        .positions_of(&'.')
+        .filter(|&pos| pos != guard)
+        // Try placing an obstruction and see if it causes a loop
+        .filter(|&pos| causes_loop(map.clone(), guard, guard_dir, pos))
+        .count();
+
+    Ok(possible_positions)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+
+    fn read_map(file_path: &str) -> Grid<char> {
+        let input = fs::read_to_string(file_path).expect("Could not read input file");
+        parse_map(&input).unwrap()
+    }
 
     #[test]
     fn test_guard_patrol_example() {
-        let visited_positions = simulate_guard_path("input/6-guard-gallivant/test-input-1.txt");
+        let visited_positions =
+            simulate_guard_path(&read_map("input/6-guard-gallivant/test-input-1.txt")).unwrap();
         assert_eq!(visited_positions, 41);
     }
 
     #[test]
     fn test_part_two_example() {
-        let count = patrol_routes("input/6-guard-gallivant/test-input-1.txt");
+        let count = patrol_routes(&read_map("input/6-guard-gallivant/test-input-1.txt")).unwrap();
         assert_eq!(count, 6);
     }
+
+    #[test]
+    fn test_parse_map_errors() {
+        let err = parse_map("..#\n.x^\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: invalid character 'x'");
+
+        let map = parse_map("..#\n...\n").unwrap();
+        assert!(matches!(
+            simulate_guard_path(&map),
+            Err(Error::MissingSection(_))
+        ));
+    }
 }
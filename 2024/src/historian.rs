@@ -1,46 +1,129 @@
-use crate::utils::Args;
+use aoc_common::error::{parse_token, Error, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 use std::collections::HashMap;
-use std::fs;
-use std::io::{self, BufRead};
 
-/// Reads the left and right lists from a file.
-fn read_lists_from_file(file_path: &str) -> (Vec<i32>, Vec<i32>) {
-    let file = fs::File::open(file_path).expect("Unable to open file");
-    let reader = io::BufReader::new(file);
+pub mod external;
+pub mod metrics;
+pub mod reconcile;
 
+pub struct Historian;
+
+impl Solution for Historian {
+    type Input = (Vec<i64>, Vec<i64>);
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lists(input)
+    }
+
+    /// Part 1: Total Distance Calculation.
+    fn part1((left_list, right_list): &Self::Input) -> Result<Answer> {
+        let mut left_list = left_list.clone();
+        let mut right_list = right_list.clone();
+
+        sort_lists(&mut left_list, &mut right_list);
+        Ok(calculate_total_distance(&left_list, &right_list)?.into())
+    }
+
+    /// Part 2: Similarity Score Calculation.
+    fn part2((left_list, right_list): &Self::Input) -> Result<Answer> {
+        Ok(calculate_similarity_score(left_list, right_list).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_lists(rng, size))
+    }
+}
+
+/// Reads the left and right lists from the puzzle input.
+/// Blank lines are skipped; every other line must hold exactly two numbers.
+pub fn parse_lists(input: &str) -> Result<(Vec<i64>, Vec<i64>)> {
     let mut left_list = Vec::new();
     let mut right_list = Vec::new();
 
-    for line in reader.lines() {
-        let line = line.expect("Unable to read line");
-        let mut parts = line.split_whitespace();
-
-        if let (Some(left), Some(right)) = (parts.next(), parts.next()) {
-            left_list.push(left.parse::<i32>().expect("Invalid number in left list"));
-            right_list.push(right.parse::<i32>().expect("Invalid number in right list"));
+    for (index, line) in input.lines().enumerate() {
+        if let Some((left, right)) = parse_pair(index, line)? {
+            left_list.push(left);
+            right_list.push(right);
         }
     }
 
-    (left_list, right_list)
+    Ok((left_list, right_list))
+}
+
+/// The two numbers of one line, or `None` for a blank line.
+fn parse_pair(index: usize, line: &str) -> Result<Option<(i64, i64)>> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+
+    match parts[..] {
+        [] => Ok(None),
+        [left, right] => Ok(Some((
+            parse_token(index, line, left, "number in left list")?,
+            parse_token(index, line, right, "number in right list")?,
+        ))),
+        _ => Err(Error::malformed(
+            index,
+            line,
+            line.trim_start(),
+            format!("expected two numbers, found {} fields", parts.len()),
+        )),
+    }
+}
+
+/// `size` lines of two five-digit location IDs; about a third of the right IDs repeat a left
+/// one so that the similarity score is not zero.
+pub fn generate_lists(rng: &mut Rng, size: usize) -> String {
+    let mut left_list = Vec::with_capacity(size);
+    let mut input = String::new();
+    for _ in 0..size {
+        let left = rng.range(10_000..=99_999);
+        left_list.push(left);
+        let right = if rng.ratio(1, 3) {
+            *rng.pick(&left_list)
+        } else {
+            rng.range(10_000..=99_999)
+        };
+        input.push_str(&format!("{}   {}\n", left, right));
+    }
+    input
 }
 
 /// Sorts the provided lists in ascending order.
-fn sort_lists(left_list: &mut Vec<i32>, right_list: &mut Vec<i32>) {
+pub fn sort_lists(left_list: &mut [i64], right_list: &mut [i64]) {
     left_list.sort();
     right_list.sort();
 }
 
-/// Calculates the total distance between the two lists.
-fn calculate_total_distance(left_list: &[i32], right_list: &[i32]) -> i32 {
-    left_list
+/// Calculates the total distance between the two lists, which must be sorted and of the
+/// same length.
+pub fn calculate_total_distance(left_list: &[i64], right_list: &[i64]) -> Result<i128> {
+    check_lengths(left_list.len() as u64, right_list.len() as u64)?;
+    Ok(left_list
         .iter()
         .zip(right_list.iter())
-        .map(|(l, r)| (l - r).abs())
-        .sum()
+        .map(|(&l, &r)| distance(l, r))
+        .sum())
+}
+
+/// Distance between two location IDs; wide enough for any pair of `i64`s.
+fn distance(left: i64, right: i64) -> i128 {
+    (i128::from(left) - i128::from(right)).abs()
+}
+
+/// Pairing sorted lists only makes sense if no number is left over on either side.
+fn check_lengths(left: u64, right: u64) -> Result<()> {
+    if left != right {
+        return Err(Error::InvalidInput(format!(
+            "the left list has {} numbers but the right list has {}",
+            left, right
+        )));
+    }
+    Ok(())
 }
 
 /// Calculates the similarity score between the two lists.
-fn calculate_similarity_score(left_list: &[i32], right_list: &[i32]) -> i32 {
+pub fn calculate_similarity_score(left_list: &[i64], right_list: &[i64]) -> i128 {
     // Count occurrences of each number in the right list.
     let mut right_counts = HashMap::new();
     for &num in right_list {
@@ -50,42 +133,23 @@ fn calculate_similarity_score(left_list: &[i32], right_list: &[i32]) -> i32 {
     // Calculate similarity score for the left list.
     left_list
         .iter()
-        .map(|&num| num * right_counts.get(&num).unwrap_or(&0))
+        .map(|&num| i128::from(num) * right_counts.get(&num).unwrap_or(&0))
         .sum()
 }
 
-/// Entry point for Part 1: Total Distance Calculation.
-pub fn run_part1() {
-    let args = Args::parse();
-    let (mut left_list, mut right_list) = read_lists_from_file(&args.file_path);
-
-    sort_lists(&mut left_list, &mut right_list);
-    let total_distance = calculate_total_distance(&left_list, &right_list);
-
-    println!("Part 1 - Total distance: {}", total_distance);
-}
-
-/// Entry point for Part 2: Similarity Score Calculation.
-pub fn run_part2() {
-    let args = Args::parse();
-    let (left_list, right_list) = read_lists_from_file(&args.file_path);
-
-    let similarity_score = calculate_similarity_score(&left_list, &right_list);
-
-    println!("Part 2 - Similarity score: {}", similarity_score);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_historian_total_distance() {
         let input_path = "input/1-historian/test-input-1.txt";
-        let (mut left_list, mut right_list) = read_lists_from_file(input_path);
+        let input = fs::read_to_string(input_path).expect("Unable to read file");
+        let (mut left_list, mut right_list) = parse_lists(&input).unwrap();
 
         sort_lists(&mut left_list, &mut right_list);
-        let total_distance = calculate_total_distance(&left_list, &right_list);
+        let total_distance = calculate_total_distance(&left_list, &right_list).unwrap();
 
         let expected_total_distance = 11;
         assert_eq!(total_distance, expected_total_distance);
@@ -94,11 +158,45 @@ mod tests {
     #[test]
     fn test_historian_similarity_score() {
         let input_path = "input/1-historian/test-input-2.txt";
-        let (left_list, right_list) = read_lists_from_file(input_path);
+        let input = fs::read_to_string(input_path).expect("Unable to read file");
+        let (left_list, right_list) = parse_lists(&input).unwrap();
 
         let similarity_score = calculate_similarity_score(&left_list, &right_list);
 
         let expected_similarity_score = 31;
         assert_eq!(similarity_score, expected_similarity_score);
     }
+
+    #[test]
+    fn test_historian_rejects_malformed_lines() {
+        let err = parse_lists("3   4\n4   x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 5: invalid number in right list 'x'"
+        );
+
+        let err = parse_lists("3   4\n\n5\n").unwrap_err();
+        assert!(matches!(err, Error::MalformedLine { line: 3, .. }));
+    }
+
+    #[test]
+    fn test_historian_wide_values_and_lengths() {
+        let (left_list, right_list) =
+            parse_lists("-9223372036854775808   9223372036854775807\n5000000000   5000000000\n")
+                .unwrap();
+        assert_eq!(
+            calculate_total_distance(&[i64::MIN], &[i64::MAX]).unwrap(),
+            2 * i128::from(i64::MAX) + 1
+        );
+        assert_eq!(
+            calculate_similarity_score(&left_list, &right_list),
+            5_000_000_000
+        );
+
+        let err = calculate_total_distance(&[1, 2], &[1]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: the left list has 2 numbers but the right list has 1"
+        );
+    }
 }
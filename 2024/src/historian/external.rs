@@ -0,0 +1,435 @@
+//! Location lists too large to hold in memory, sorted on disk for scoring with
+//! [`super::metrics`].
+//!
+//! The input is read in chunks of pairs. Each column of a chunk is sorted and written to disk
+//! as a run of little-endian `i64`s, and the runs of a column are merged back into one sorted
+//! stream. Runs are merged in passes of at most [`ExternalSort::fan_in`] at a time, so memory
+//! use is bounded by the chunk size and that many read buffers, and so are open files.
+
+use super::{check_lengths, parse_pair};
+use aoc_common::error::Result;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How [`sort_lists`] splits its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSort {
+    /// Pairs sorted in memory at a time, at 16 bytes per pair.
+    pub chunk_size: usize,
+    /// Where the sorted runs are written; a directory of their own is made inside.
+    pub temp_dir: PathBuf,
+    /// Most runs read at once. Columns with more runs are merged into longer runs first.
+    pub fan_in: usize,
+}
+
+impl Default for ExternalSort {
+    fn default() -> Self {
+        ExternalSort {
+            chunk_size: 1 << 22,
+            temp_dir: env::temp_dir(),
+            fan_in: 64,
+        }
+    }
+}
+
+/// Both columns of a location list, sorted into runs on disk. The runs are deleted when this
+/// is dropped.
+#[derive(Debug)]
+pub struct SortedLists {
+    dir: PathBuf,
+    left: Vec<PathBuf>,
+    right: Vec<PathBuf>,
+    left_len: u64,
+    right_len: u64,
+}
+
+impl SortedLists {
+    /// Number of lines with a pair of numbers.
+    pub fn pairs(&self) -> u64 {
+        self.left_len
+    }
+
+    /// The left column, in ascending order.
+    pub fn left(&self) -> Result<Merge> {
+        Merge::open(&self.left)
+    }
+
+    /// The right column, in ascending order.
+    pub fn right(&self) -> Result<Merge> {
+        Merge::open(&self.right)
+    }
+
+    /// Passes every pair of the sorted columns to `pair`, in order. Both columns must have the
+    /// same length.
+    pub fn for_each_pair(&self, mut pair: impl FnMut(i64, i64) -> Result<()>) -> Result<()> {
+        check_lengths(self.left_len, self.right_len)?;
+        for (left, right) in self.left()?.zip(self.right()?) {
+            pair(left?, right?)?;
+        }
+        Ok(())
+    }
+
+    /// Passes every distinct ID of either column to `id`, in ascending order, with how often
+    /// it occurs in each column.
+    pub fn for_each_id(&self, id: impl FnMut(IdCounts) -> Result<()>) -> Result<()> {
+        join_counts(self.left()?, self.right()?, id)
+    }
+
+    /// Sorts the chunk of each column and writes it as the next run.
+    fn spill(&mut self, left: &mut Vec<i64>, right: &mut Vec<i64>) -> Result<()> {
+        let run = self.left.len();
+        self.left_len += left.len() as u64;
+        self.right_len += right.len() as u64;
+        self.left
+            .push(write_run(self.dir.join(format!("left-{}.run", run)), left)?);
+        self.right.push(write_run(
+            self.dir.join(format!("right-{}.run", run)),
+            right,
+        )?);
+        Ok(())
+    }
+}
+
+impl Drop for SortedLists {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Reads the location lists from `reader` and sorts both columns on disk. The lines are
+/// parsed like [`super::parse_lists`] does.
+pub fn sort_lists(reader: impl BufRead, options: &ExternalSort) -> Result<SortedLists> {
+    static SORTS: AtomicUsize = AtomicUsize::new(0);
+    let dir = options.temp_dir.join(format!(
+        "aoc-historian-{}-{}",
+        process::id(),
+        SORTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir)?;
+
+    let mut lists = SortedLists {
+        dir,
+        left: Vec::new(),
+        right: Vec::new(),
+        left_len: 0,
+        right_len: 0,
+    };
+    let chunk_size = options.chunk_size.max(1);
+    let mut left = Vec::with_capacity(chunk_size);
+    let mut right = Vec::with_capacity(chunk_size);
+
+    for (index, line) in reader.lines().enumerate() {
+        if let Some((l, r)) = parse_pair(index, &line?)? {
+            left.push(l);
+            right.push(r);
+            if left.len() == chunk_size {
+                lists.spill(&mut left, &mut right)?;
+            }
+        }
+    }
+    if !left.is_empty() {
+        lists.spill(&mut left, &mut right)?;
+    }
+
+    let fan_in = options.fan_in.max(2);
+    lists.left = merge_runs(&lists.dir, "left", mem::take(&mut lists.left), fan_in)?;
+    lists.right = merge_runs(&lists.dir, "right", mem::take(&mut lists.right), fan_in)?;
+    Ok(lists)
+}
+
+/// Merges groups of `fan_in` runs into one, pass after pass, until at most `fan_in` runs are
+/// left. The merged runs are deleted.
+fn merge_runs(dir: &Path, column: &str, runs: Vec<PathBuf>, fan_in: usize) -> Result<Vec<PathBuf>> {
+    let mut runs = runs;
+    let mut pass = 0;
+    while runs.len() > fan_in {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(fan_in));
+        for (group, paths) in runs.chunks(fan_in).enumerate() {
+            let path = dir.join(format!("{}-pass{}-{}.run", column, pass, group));
+            let mut writer = BufWriter::new(File::create(&path)?);
+            for value in Merge::open(paths)? {
+                writer.write_all(&value?.to_le_bytes())?;
+            }
+            writer.flush()?;
+            for path in paths {
+                fs::remove_file(path)?;
+            }
+            merged.push(path);
+        }
+        runs = merged;
+        pass += 1;
+    }
+    Ok(runs)
+}
+
+/// Sorts `values` and writes them to `path`, leaving `values` empty for the next chunk.
+fn write_run(path: PathBuf, values: &mut Vec<i64>) -> Result<PathBuf> {
+    values.sort_unstable();
+    let mut writer = BufWriter::new(File::create(&path)?);
+    for value in values.drain(..) {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(path)
+}
+
+/// The values of several sorted runs, merged into one ascending stream.
+pub struct Merge {
+    runs: Vec<BufReader<File>>,
+    /// The smallest unread value of every run that is not exhausted yet.
+    heads: BinaryHeap<Reverse<(i64, usize)>>,
+}
+
+impl Merge {
+    fn open(paths: &[PathBuf]) -> Result<Self> {
+        let mut merge = Merge {
+            runs: paths
+                .iter()
+                .map(|path| File::open(path).map(BufReader::new))
+                .collect::<io::Result<_>>()?,
+            heads: BinaryHeap::new(),
+        };
+        for run in 0..merge.runs.len() {
+            merge.advance(run)?;
+        }
+        Ok(merge)
+    }
+
+    /// Reads the next value of `run` into the heap, if there is one.
+    fn advance(&mut self, run: usize) -> Result<()> {
+        let mut bytes = [0; 8];
+        match self.runs[run].read_exact(&mut bytes) {
+            Ok(()) => self.heads.push(Reverse((i64::from_le_bytes(bytes), run))),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Merge {
+    type Item = Result<i64>;
+
+    fn next(&mut self) -> Option<Result<i64>> {
+        let Reverse((value, run)) = self.heads.pop()?;
+        Some(self.advance(run).map(|()| value))
+    }
+}
+
+/// One of the two location lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// The name used in exported tables.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+}
+
+/// How often one ID occurs in each list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdCounts {
+    pub id: i64,
+    pub left: u64,
+    pub right: u64,
+}
+
+impl IdCounts {
+    /// The list that holds the ID, if only one of them does.
+    pub fn only_in(&self) -> Option<Side> {
+        match (self.left, self.right) {
+            (_, 0) => Some(Side::Left),
+            (0, _) => Some(Side::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Collapses a sorted stream into each distinct value and how often it occurs.
+struct Counts<I> {
+    values: I,
+    pending: Option<i64>,
+}
+
+impl<I: Iterator<Item = Result<i64>>> Iterator for Counts<I> {
+    type Item = Result<(i64, u64)>;
+
+    fn next(&mut self) -> Option<Result<(i64, u64)>> {
+        let value = match self.pending.take() {
+            Some(value) => value,
+            None => match self.values.next()? {
+                Ok(value) => value,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+
+        let mut count = 1;
+        for next in self.values.by_ref() {
+            match next {
+                Ok(next) if next == value => count += 1,
+                Ok(next) => {
+                    self.pending = Some(next);
+                    break;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok((value, count)))
+    }
+}
+
+/// Each distinct value of the sorted stream `values` with its number of occurrences.
+fn counts<I: Iterator<Item = Result<i64>>>(values: I) -> Counts<I> {
+    Counts {
+        values,
+        pending: None,
+    }
+}
+
+/// Joins two sorted streams on their distinct values, passing each value of either stream to
+/// `id` in ascending order.
+pub(super) fn join_counts(
+    left: impl Iterator<Item = Result<i64>>,
+    right: impl Iterator<Item = Result<i64>>,
+    mut id: impl FnMut(IdCounts) -> Result<()>,
+) -> Result<()> {
+    let mut left_counts = counts(left);
+    let mut right_counts = counts(right);
+    let mut left = left_counts.next().transpose()?;
+    let mut right = right_counts.next().transpose()?;
+    loop {
+        let value = match (left, right) {
+            (Some((l, _)), Some((r, _))) => l.min(r),
+            (Some((l, _)), None) => l,
+            (None, Some((r, _))) => r,
+            (None, None) => return Ok(()),
+        };
+
+        let mut counts = IdCounts {
+            id: value,
+            left: 0,
+            right: 0,
+        };
+        if let Some((_, count)) = left.filter(|&(l, _)| l == value) {
+            counts.left = count;
+            left = left_counts.next().transpose()?;
+        }
+        if let Some((_, count)) = right.filter(|&(r, _)| r == value) {
+            counts.right = count;
+            right = right_counts.next().transpose()?;
+        }
+        id(counts)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(chunk_size: usize) -> ExternalSort {
+        ExternalSort {
+            chunk_size,
+            ..ExternalSort::default()
+        }
+    }
+
+    #[test]
+    fn test_external_sort_merges_runs() {
+        let input = "3   4\n4   3\n2   5\n\n1   3\n3   9\n3   3\n";
+        for chunk_size in [1, 2, 6, 100] {
+            let lists = sort_lists(input.as_bytes(), &options(chunk_size)).unwrap();
+            assert_eq!(lists.pairs(), 6);
+            assert_eq!(lists.left.len(), 6_usize.div_ceil(chunk_size));
+
+            let mut pairs = Vec::new();
+            lists
+                .for_each_pair(|left, right| {
+                    pairs.push((left, right));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(pairs, [(1, 3), (2, 3), (3, 3), (3, 4), (3, 5), (4, 9)]);
+
+            let mut ids = Vec::new();
+            lists
+                .for_each_id(|counts| {
+                    ids.push((counts.id, counts.left, counts.right, counts.only_in()));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(
+                ids,
+                [
+                    (1, 1, 0, Some(Side::Left)),
+                    (2, 1, 0, Some(Side::Left)),
+                    (3, 3, 3, None),
+                    (4, 1, 1, None),
+                    (5, 0, 1, Some(Side::Right)),
+                    (9, 0, 1, Some(Side::Right)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_external_merge_passes() {
+        let input: String = (0..50)
+            .map(|i| format!("{} {}\n", (i * 7) % 50, 49 - i))
+            .collect();
+        for fan_in in [2, 3, 64] {
+            let options = ExternalSort {
+                chunk_size: 1,
+                fan_in,
+                ..ExternalSort::default()
+            };
+            let lists = sort_lists(input.as_bytes(), &options).unwrap();
+            assert!(lists.left.len() <= fan_in && lists.right.len() <= fan_in);
+            assert_eq!(
+                fs::read_dir(&lists.dir).unwrap().count(),
+                2 * lists.left.len()
+            );
+
+            let mut pairs = Vec::new();
+            lists
+                .for_each_pair(|left, right| {
+                    pairs.push((left, right));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(pairs, (0..50).map(|i| (i, i)).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_external_cleanup_and_errors() {
+        let lists = sort_lists("1 2\n".as_bytes(), &options(2)).unwrap();
+        let dir = lists.dir.clone();
+        assert!(dir.exists());
+        drop(lists);
+        assert!(!dir.exists());
+
+        let empty = sort_lists("".as_bytes(), &options(2)).unwrap();
+        assert_eq!(empty.pairs(), 0);
+        assert!(empty.for_each_pair(|_, _| panic!("no pairs")).is_ok());
+
+        let err = sort_lists("1 2\n3\n".as_bytes(), &options(2)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected two numbers, found 1 fields"
+        );
+    }
+}
@@ -0,0 +1,477 @@
+//! Ways of scoring how far apart the two location lists are, beyond the puzzle's total
+//! distance and similarity score.
+//!
+//! A metric is fed in up to two passes: the pairs of the sorted lists, and every distinct ID
+//! with its count in each list. The same metrics score lists held in memory
+//! ([`score_lists`]) and lists sorted on disk ([`score_sorted`]).
+
+use super::external::{join_counts, IdCounts, SortedLists};
+use super::{check_lengths, distance};
+use aoc_common::error::{Error, Result};
+use std::fmt;
+
+/// The pass a metric needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    /// The pairs of the sorted lists, which must have the same length.
+    Pairs,
+    /// Every distinct ID of either list, with how often it occurs in each.
+    Ids,
+}
+
+/// The value of a metric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Score {
+    Integer(i128),
+    Real(f64),
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Integer(n) => write!(f, "{}", n),
+            Score::Real(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+/// A score accumulated over one pass of the lists.
+pub trait Metric {
+    fn pass(&self) -> Pass;
+
+    /// Called for every pair of the sorted lists, in order, if the metric needs [`Pass::Pairs`].
+    fn pair(&mut self, _left: i64, _right: i64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for every distinct ID, in ascending order, if the metric needs [`Pass::Ids`].
+    fn id(&mut self, _counts: IdCounts) -> Result<()> {
+        Ok(())
+    }
+
+    fn value(&self) -> Score;
+}
+
+/// A metric that can be picked by name.
+pub struct MetricEntry {
+    pub name: &'static str,
+    /// Shown next to the value in text output.
+    pub label: &'static str,
+    pub new: fn() -> Box<dyn Metric>,
+}
+
+/// Every metric, by name.
+pub const METRICS: &[MetricEntry] = &[
+    MetricEntry {
+        name: "distance",
+        label: "Total distance",
+        new: || Box::<TotalDistance>::default(),
+    },
+    MetricEntry {
+        name: "squared",
+        label: "Squared distance",
+        new: || Box::<SquaredDistance>::default(),
+    },
+    MetricEntry {
+        name: "emd",
+        label: "Earth mover's distance",
+        new: || Box::<EarthMovers>::default(),
+    },
+    MetricEntry {
+        name: "similarity",
+        label: "Similarity score",
+        new: || Box::<Similarity>::default(),
+    },
+    MetricEntry {
+        name: "jaccard",
+        label: "Jaccard similarity",
+        new: || Box::<Jaccard>::default(),
+    },
+    MetricEntry {
+        name: "overlap",
+        label: "Overlap coefficient",
+        new: || Box::<Overlap>::default(),
+    },
+    MetricEntry {
+        name: "weighted",
+        label: "Weighted Jaccard",
+        new: || Box::<WeightedJaccard>::default(),
+    },
+];
+
+/// Looks up a metric of [`METRICS`] by name.
+pub fn find_metric(name: &str) -> Result<&'static MetricEntry> {
+    METRICS
+        .iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = METRICS.iter().map(|entry| entry.name).collect();
+            Error::InvalidInput(format!(
+                "unknown metric '{}', expected one of {}",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+/// Scores lists held in memory; they need not be sorted.
+pub fn score_lists(left: &[i64], right: &[i64], metrics: &mut [Box<dyn Metric>]) -> Result<()> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    super::sort_lists(&mut left, &mut right);
+
+    if needs(metrics, Pass::Pairs) {
+        check_lengths(left.len() as u64, right.len() as u64)?;
+        for (&l, &r) in left.iter().zip(&right) {
+            feed_pair(metrics, l, r)?;
+        }
+    }
+    if needs(metrics, Pass::Ids) {
+        let (left, right) = (left.iter().copied().map(Ok), right.iter().copied().map(Ok));
+        join_counts(left, right, |counts| feed_id(metrics, counts))?;
+    }
+    Ok(())
+}
+
+/// Scores lists sorted on disk, reading each column once per pass the metrics need.
+pub fn score_sorted(lists: &SortedLists, metrics: &mut [Box<dyn Metric>]) -> Result<()> {
+    if needs(metrics, Pass::Pairs) {
+        lists.for_each_pair(|l, r| feed_pair(metrics, l, r))?;
+    }
+    if needs(metrics, Pass::Ids) {
+        lists.for_each_id(|counts| feed_id(metrics, counts))?;
+    }
+    Ok(())
+}
+
+fn needs(metrics: &[Box<dyn Metric>], pass: Pass) -> bool {
+    metrics.iter().any(|metric| metric.pass() == pass)
+}
+
+fn feed_pair(metrics: &mut [Box<dyn Metric>], left: i64, right: i64) -> Result<()> {
+    for metric in metrics.iter_mut().filter(|m| m.pass() == Pass::Pairs) {
+        metric.pair(left, right)?;
+    }
+    Ok(())
+}
+
+fn feed_id(metrics: &mut [Box<dyn Metric>], counts: IdCounts) -> Result<()> {
+    for metric in metrics.iter_mut().filter(|m| m.pass() == Pass::Ids) {
+        metric.id(counts)?;
+    }
+    Ok(())
+}
+
+fn overflow(metric: &str) -> Error {
+    Error::InvalidInput(format!("the {} does not fit in 128 bits", metric))
+}
+
+/// `a / b`, or `empty` when there is nothing to divide.
+fn ratio(a: u128, b: u128, empty: f64) -> Score {
+    Score::Real(if b == 0 { empty } else { a as f64 / b as f64 })
+}
+
+/// The puzzle's part 1: the sum of `|left - right|` over the sorted pairs.
+#[derive(Debug, Default)]
+pub struct TotalDistance {
+    total: i128,
+}
+
+impl Metric for TotalDistance {
+    fn pass(&self) -> Pass {
+        Pass::Pairs
+    }
+
+    fn pair(&mut self, left: i64, right: i64) -> Result<()> {
+        self.total = self
+            .total
+            .checked_add(distance(left, right))
+            .ok_or_else(|| overflow("total distance"))?;
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        Score::Integer(self.total)
+    }
+}
+
+/// The sum of `(left - right)^2` over the sorted pairs, which weighs a few far-off pairs above
+/// many close ones.
+#[derive(Debug, Default)]
+pub struct SquaredDistance {
+    total: i128,
+}
+
+impl Metric for SquaredDistance {
+    fn pass(&self) -> Pass {
+        Pass::Pairs
+    }
+
+    fn pair(&mut self, left: i64, right: i64) -> Result<()> {
+        let distance = distance(left, right);
+        self.total = distance
+            .checked_mul(distance)
+            .and_then(|square| self.total.checked_add(square))
+            .ok_or_else(|| overflow("squared distance"))?;
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        Score::Integer(self.total)
+    }
+}
+
+/// The earth mover's distance between the two lists as multisets of equal weight: the
+/// average distance an ID moves. On a line the cheapest way to move one multiset onto the
+/// other pairs them in sorted order, so this is the total distance over the number of pairs.
+#[derive(Debug, Default)]
+pub struct EarthMovers {
+    total: i128,
+    pairs: u64,
+}
+
+impl Metric for EarthMovers {
+    fn pass(&self) -> Pass {
+        Pass::Pairs
+    }
+
+    fn pair(&mut self, left: i64, right: i64) -> Result<()> {
+        self.total = self
+            .total
+            .checked_add(distance(left, right))
+            .ok_or_else(|| overflow("earth mover's distance"))?;
+        self.pairs += 1;
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        match self.pairs {
+            0 => Score::Real(0.0),
+            pairs => Score::Real(self.total as f64 / pairs as f64),
+        }
+    }
+}
+
+/// The puzzle's part 2: every left ID times how often it occurs in the right list.
+#[derive(Debug, Default)]
+pub struct Similarity {
+    total: i128,
+}
+
+impl Metric for Similarity {
+    fn pass(&self) -> Pass {
+        Pass::Ids
+    }
+
+    fn id(&mut self, counts: IdCounts) -> Result<()> {
+        self.total = i128::from(counts.left)
+            .checked_mul(counts.right.into())
+            .and_then(|pairs| pairs.checked_mul(counts.id.into()))
+            .and_then(|score| self.total.checked_add(score))
+            .ok_or_else(|| overflow("similarity score"))?;
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        Score::Integer(self.total)
+    }
+}
+
+/// Size of the multiset intersection over the size of the union: 1 for lists with the same
+/// IDs, 0 for lists with none in common. Two empty lists count as the same.
+#[derive(Debug, Default)]
+pub struct Jaccard {
+    shared: u128,
+    union: u128,
+}
+
+impl Metric for Jaccard {
+    fn pass(&self) -> Pass {
+        Pass::Ids
+    }
+
+    fn id(&mut self, counts: IdCounts) -> Result<()> {
+        self.shared += u128::from(counts.left.min(counts.right));
+        self.union += u128::from(counts.left.max(counts.right));
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        ratio(self.shared, self.union, 1.0)
+    }
+}
+
+/// Size of the multiset intersection over the size of the shorter list, so that a list
+/// contained in the other scores 1. Scores 0 if either list is empty.
+#[derive(Debug, Default)]
+pub struct Overlap {
+    shared: u128,
+    left: u128,
+    right: u128,
+}
+
+impl Metric for Overlap {
+    fn pass(&self) -> Pass {
+        Pass::Ids
+    }
+
+    fn id(&mut self, counts: IdCounts) -> Result<()> {
+        self.shared += u128::from(counts.left.min(counts.right));
+        self.left += u128::from(counts.left);
+        self.right += u128::from(counts.right);
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        ratio(self.shared, self.left.min(self.right), 0.0)
+    }
+}
+
+/// Like [`Jaccard`], but each occurrence weighs as much as the magnitude of its ID, the way
+/// the similarity score weighs by ID.
+#[derive(Debug, Default)]
+pub struct WeightedJaccard {
+    shared: u128,
+    union: u128,
+}
+
+impl Metric for WeightedJaccard {
+    fn pass(&self) -> Pass {
+        Pass::Ids
+    }
+
+    fn id(&mut self, counts: IdCounts) -> Result<()> {
+        let weight = u128::from(counts.id.unsigned_abs());
+        let weighted = |count: u64| weight.checked_mul(count.into());
+        self.shared = weighted(counts.left.min(counts.right))
+            .and_then(|shared| self.shared.checked_add(shared))
+            .ok_or_else(|| overflow("weighted Jaccard similarity"))?;
+        self.union = weighted(counts.left.max(counts.right))
+            .and_then(|union| self.union.checked_add(union))
+            .ok_or_else(|| overflow("weighted Jaccard similarity"))?;
+        Ok(())
+    }
+
+    fn value(&self) -> Score {
+        ratio(self.shared, self.union, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::external::{sort_lists, ExternalSort};
+    use super::super::{calculate_similarity_score, calculate_total_distance, parse_lists};
+    use super::*;
+    use aoc_common::rng::Rng;
+
+    fn all_metrics() -> Vec<Box<dyn Metric>> {
+        METRICS.iter().map(|entry| (entry.new)()).collect()
+    }
+
+    /// The value of every metric of [`all_metrics`], next to its name.
+    fn values(metrics: &[Box<dyn Metric>]) -> Vec<(&'static str, Score)> {
+        METRICS
+            .iter()
+            .zip(metrics)
+            .map(|(entry, metric)| (entry.name, metric.value()))
+            .collect()
+    }
+
+    fn value(metrics: &[Box<dyn Metric>], name: &str) -> Score {
+        let index = METRICS.iter().position(|entry| entry.name == name).unwrap();
+        metrics[index].value()
+    }
+
+    #[test]
+    fn test_metrics_example() {
+        // Sorted pairs: 1-3, 2-3, 3-3, 3-4, 3-5, 4-9.
+        let (left, right) = parse_lists("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n").unwrap();
+        let mut metrics = all_metrics();
+        score_lists(&left, &right, &mut metrics).unwrap();
+
+        // Both lists hold three 3s and one 4; the union has eight occurrences.
+        assert_eq!(
+            values(&metrics),
+            [
+                ("distance", Score::Integer(11)),
+                ("squared", Score::Integer(35)),
+                ("emd", Score::Real(11.0 / 6.0)),
+                ("similarity", Score::Integer(31)),
+                ("jaccard", Score::Real(4.0 / 8.0)),
+                ("overlap", Score::Real(4.0 / 6.0)),
+                (
+                    "weighted",
+                    Score::Real((3.0 * 3.0 + 4.0) / (1.0 + 2.0 + 9.0 + 4.0 + 5.0 + 9.0))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metrics_edge_cases() {
+        let mut metrics = all_metrics();
+        score_lists(&[], &[], &mut metrics).unwrap();
+        assert_eq!(
+            values(&metrics),
+            [
+                ("distance", Score::Integer(0)),
+                ("squared", Score::Integer(0)),
+                ("emd", Score::Real(0.0)),
+                ("similarity", Score::Integer(0)),
+                ("jaccard", Score::Real(1.0)),
+                ("overlap", Score::Real(0.0)),
+                ("weighted", Score::Real(1.0)),
+            ]
+        );
+
+        let mut metrics = vec![(find_metric("jaccard").unwrap().new)()];
+        score_lists(&[1, 2], &[3], &mut metrics).unwrap();
+        assert_eq!(metrics[0].value(), Score::Real(0.0));
+
+        let mut metrics = vec![(find_metric("emd").unwrap().new)()];
+        let err = score_lists(&[1, 2], &[3], &mut metrics).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: the left list has 2 numbers but the right list has 1"
+        );
+
+        let mut metrics = vec![(find_metric("squared").unwrap().new)()];
+        let err = score_lists(&[i64::MIN], &[i64::MAX], &mut metrics).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: the squared distance does not fit in 128 bits"
+        );
+
+        assert_eq!(
+            find_metric("cosine").err().unwrap().to_string(),
+            "invalid input: unknown metric 'cosine', expected one of \
+             distance, squared, emd, similarity, jaccard, overlap, weighted"
+        );
+    }
+
+    #[test]
+    fn test_metrics_match_puzzle_and_external() {
+        let mut rng = Rng::new(23);
+        for size in [1, 10, 500] {
+            let input = super::super::generate_lists(&mut rng, size);
+            let (mut left, mut right) = parse_lists(&input).unwrap();
+
+            let mut in_memory = all_metrics();
+            score_lists(&left, &right, &mut in_memory).unwrap();
+            let mut external = all_metrics();
+            let options = ExternalSort {
+                chunk_size: 7,
+                ..ExternalSort::default()
+            };
+            let lists = sort_lists(input.as_bytes(), &options).unwrap();
+            score_sorted(&lists, &mut external).unwrap();
+            assert_eq!(values(&in_memory), values(&external), "size {}", size);
+
+            super::super::sort_lists(&mut left, &mut right);
+            let distance = calculate_total_distance(&left, &right).unwrap();
+            let similarity = calculate_similarity_score(&left, &right);
+            assert_eq!(value(&in_memory, "distance"), Score::Integer(distance));
+            assert_eq!(value(&in_memory, "similarity"), Score::Integer(similarity));
+        }
+    }
+}
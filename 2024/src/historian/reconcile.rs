@@ -0,0 +1,221 @@
+//! Which location IDs the two lists disagree on, rather than a single score.
+//!
+//! Works on lists sorted by [`super::external::sort_lists`], in two passes: one over the sorted
+//! pairs for the largest distances and the distance histogram, and one joining the counts of
+//! each distinct ID.
+
+use super::distance;
+use super::external::{IdCounts, Side, SortedLists};
+use aoc_common::error::Result;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A pair of the sorted lists, with its 0-based position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pair {
+    pub index: u64,
+    pub left: i64,
+    pub right: i64,
+}
+
+impl Pair {
+    pub fn distance(&self) -> i128 {
+        distance(self.left, self.right)
+    }
+}
+
+/// Pairs whose distance is in `from..=to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub from: i128,
+    pub to: i128,
+    pub pairs: u64,
+}
+
+/// Everything but the per-ID counts, which are passed on one by one as they are found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconciliation {
+    pub pairs: u64,
+    pub distinct_ids: u64,
+    pub left_only: u64,
+    pub right_only: u64,
+    /// The pairs with the largest distances, largest first; ties keep their sorted order.
+    pub largest: Vec<Pair>,
+    /// Distances in power-of-two buckets: 0, 1, 2-3, 4-7 and so on, up to the largest.
+    pub histogram: Vec<Bucket>,
+}
+
+/// Reconciles the sorted lists, keeping the `top` largest distances. Every distinct ID of
+/// either list is passed to `id_counts` in ascending order.
+pub fn reconcile(
+    lists: &SortedLists,
+    top: usize,
+    mut id_counts: impl FnMut(IdCounts) -> Result<()>,
+) -> Result<Reconciliation> {
+    // A min-heap of the largest distances so far: the smallest of them is replaced first, and
+    // of equal distances the later pair.
+    let mut largest = BinaryHeap::with_capacity(top + 1);
+    let mut buckets = vec![0; BUCKETS];
+    let mut index = 0;
+    lists.for_each_pair(|left, right| {
+        let pair = Pair { index, left, right };
+        index += 1;
+        let distance = pair.distance();
+        buckets[bucket_of(distance)] += 1;
+
+        largest.push(Reverse((distance, Reverse(pair))));
+        if largest.len() > top {
+            largest.pop();
+        }
+        Ok(())
+    })?;
+
+    let mut reconciliation = Reconciliation {
+        pairs: lists.pairs(),
+        distinct_ids: 0,
+        left_only: 0,
+        right_only: 0,
+        largest: largest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(pair)))| pair)
+            .collect(),
+        histogram: histogram(&buckets),
+    };
+
+    lists.for_each_id(|counts| {
+        reconciliation.distinct_ids += 1;
+        match counts.only_in() {
+            Some(Side::Left) => reconciliation.left_only += 1,
+            Some(Side::Right) => reconciliation.right_only += 1,
+            None => {}
+        }
+        id_counts(counts)
+    })?;
+
+    Ok(reconciliation)
+}
+
+/// Enough buckets for the largest distance between two `i64`s, just below `2^64`.
+const BUCKETS: usize = 65;
+
+/// Bucket 0 holds distance 0 and bucket `k` the distances from `2^(k-1)` to `2^k - 1`.
+fn bucket_of(distance: i128) -> usize {
+    (128 - distance.leading_zeros()) as usize
+}
+
+/// The buckets up to the last non-empty one.
+fn histogram(buckets: &[u64]) -> Vec<Bucket> {
+    let used = buckets
+        .iter()
+        .rposition(|&pairs| pairs > 0)
+        .map_or(0, |last| last + 1);
+    buckets[..used]
+        .iter()
+        .enumerate()
+        .map(|(bucket, &pairs)| {
+            let (from, to) = match bucket {
+                0 => (0, 0),
+                _ => (1 << (bucket - 1), (1 << (bucket - 1)) * 2 - 1),
+            };
+            Bucket { from, to, pairs }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::external::{sort_lists, ExternalSort};
+    use super::*;
+
+    #[test]
+    fn test_reconcile_example() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let options = ExternalSort {
+            chunk_size: 4,
+            ..ExternalSort::default()
+        };
+        let lists = sort_lists(input.as_bytes(), &options).unwrap();
+
+        let mut ids = Vec::new();
+        let reconciliation = reconcile(&lists, 2, |counts| {
+            ids.push((counts.id, counts.left, counts.right, counts.only_in()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            ids,
+            vec![
+                (1, 1, 0, Some(Side::Left)),
+                (2, 1, 0, Some(Side::Left)),
+                (3, 3, 3, None),
+                (4, 1, 1, None),
+                (5, 0, 1, Some(Side::Right)),
+                (9, 0, 1, Some(Side::Right)),
+            ]
+        );
+
+        // Sorted pairs: 1-3, 2-3, 3-3, 3-4, 3-5, 4-9.
+        assert_eq!(
+            reconciliation,
+            Reconciliation {
+                pairs: 6,
+                distinct_ids: 6,
+                left_only: 2,
+                right_only: 2,
+                largest: vec![
+                    Pair {
+                        index: 5,
+                        left: 4,
+                        right: 9,
+                    },
+                    Pair {
+                        index: 0,
+                        left: 1,
+                        right: 3,
+                    },
+                ],
+                histogram: vec![
+                    Bucket {
+                        from: 0,
+                        to: 0,
+                        pairs: 1,
+                    },
+                    Bucket {
+                        from: 1,
+                        to: 1,
+                        pairs: 2,
+                    },
+                    Bucket {
+                        from: 2,
+                        to: 3,
+                        pairs: 2,
+                    },
+                    Bucket {
+                        from: 4,
+                        to: 7,
+                        pairs: 1,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        assert_eq!(bucket_of(0), 0);
+        assert_eq!(bucket_of(1), 1);
+        assert_eq!(bucket_of(3), 2);
+        assert_eq!(bucket_of(4), 3);
+        assert_eq!(bucket_of(distance(i64::MIN, i64::MAX)), 64);
+        assert!(histogram(&[0; BUCKETS]).is_empty());
+
+        let top = histogram(&{
+            let mut buckets = [0; BUCKETS];
+            buckets[64] = 1;
+            buckets
+        });
+        assert_eq!(top[64].to, distance(i64::MIN, i64::MAX));
+    }
+}
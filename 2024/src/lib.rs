@@ -0,0 +1,37 @@
+//! Advent of Code 2024 solutions.
+//!
+//! Every day lives in its own module and implements [`aoc_common::solution::Solution`];
+//! [`DAYS`] registers them by day number so a runner can drive any of them.
+
+pub mod bridge_repair;
+pub mod ceres_search;
+pub mod claw_contraption;
+pub mod disk_fragmentation;
+pub mod guard_gallivant;
+pub mod historian;
+pub mod hoof_it;
+pub mod mull_it_over;
+pub mod plutonian_pebbles;
+pub mod queue_order_verification;
+pub mod red_nosed_report;
+pub mod resonant_collinearity;
+pub mod restroom_redoubt;
+
+use aoc_common::solution::Day;
+
+/// Every solved day, in puzzle order.
+pub const DAYS: &[Day] = &[
+    Day::new::<historian::Historian>(1, "historian"),
+    Day::new::<red_nosed_report::RedNosedReport>(2, "red-nosed-report"),
+    Day::new::<mull_it_over::MullItOver>(3, "mull-it-over"),
+    Day::new::<ceres_search::CeresSearch>(4, "ceres-search"),
+    Day::new::<queue_order_verification::QueueOrderVerification>(5, "queue-order-verification"),
+    Day::new::<guard_gallivant::GuardGallivant>(6, "guard-gallivant"),
+    Day::new::<bridge_repair::BridgeRepair>(7, "bridge-repair"),
+    Day::new::<resonant_collinearity::ResonantCollinearity>(8, "resonant-collinearity"),
+    Day::new::<disk_fragmentation::DiskFragmentation>(9, "disk-fragmentation"),
+    Day::new::<hoof_it::HoofIt>(10, "hoof-it"),
+    Day::new::<plutonian_pebbles::PlutonianPebbles>(11, "plutonian-pebbles"),
+    Day::new::<claw_contraption::ClawContraption>(13, "claw-contraption"),
+    Day::new::<restroom_redoubt::RestroomRedoubt>(14, "restroom-redoubt"),
+];
@@ -1,18 +1,10 @@
-mod bridge_repair;
-mod ceres_search;
-mod claw_contraption;
-mod disk_fragmentation;
-mod guard_gallivant;
-mod historian;
-mod hoof_it;
-mod mull_it_over;
-mod plutonian_pebbles;
-mod queue_order_verification;
-mod red_nosed_report;
-mod resonant_collinearity;
-mod restroom_redoubt;
-mod utils;
+use advent_of_code_2024::DAYS;
+use aoc_common::bench::CountingAllocator;
+use std::process::ExitCode;
 
-fn main() {
-    restroom_redoubt::run_part1();
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() -> ExitCode {
+    aoc_common::runner::main("aoc2024", DAYS)
 }
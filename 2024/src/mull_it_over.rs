@@ -1,50 +1,103 @@
-use crate::utils::Args;
-use regex::Regex;
-use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-pub fn run_part1() {
-    let args = Args::parse();
-    let sum = process_memory(&args.file_path);
-    println!("Sum of all valid mul instructions: {}", sum);
+use aoc_common::error::{Error, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
+use regex::{Captures, Regex};
+
+pub struct MullItOver;
+
+impl Solution for MullItOver {
+    type Input = String;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(memory: &Self::Input) -> Result<Answer> {
+        Ok(process_memory(memory)?.into())
+    }
+
+    /// Part 2: Processes valid `mul` instructions with conditional handling.
+    fn part2(memory: &Self::Input) -> Result<Answer> {
+        Ok(process_memory_with_conditionals(memory)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_memory(rng, size))
+    }
 }
 
-/// Processes the corrupted memory and returns the sum of valid `mul` instructions.
-fn process_memory(file_path: &str) -> i32 {
-    let file_content = fs::read_to_string(file_path).expect("Failed to read the input file");
+/// Corrupted memory with `size` instructions: valid and broken `mul`s and `do()`/`don't()`
+/// switches, separated by runs of noise.
+pub fn generate_memory(rng: &mut Rng, size: usize) -> String {
+    const NOISE: &[u8] = b"!@#$%^&*()[]{}<>+-_=;:',./? mulxdont";
+    let mut memory = String::new();
+    for _ in 0..size {
+        for _ in 0..rng.range(0..=8) {
+            memory.push(*rng.pick(NOISE) as char);
+        }
+        let (a, b) = (rng.range(1..=999), rng.range(1..=999));
+        let instruction = match rng.range(0..=9) {
+            0 => "do()".to_string(),
+            1 => "don't()".to_string(),
+            2 => format!("mul({},{}]", a, b),
+            3 => format!("mul ( {} , {} )", a, b),
+            4 => format!("mul({}*{})", a, b),
+            _ => format!("mul({},{})", a, b),
+        };
+        memory.push_str(&instruction);
+    }
+    memory.push('\n');
+    memory
+}
 
+/// Processes the corrupted memory and returns the sum of valid `mul` instructions.
+pub fn process_memory(memory: &str) -> Result<i64> {
     let re = Regex::new(r"mul\((\d+),(\d+)\)").expect("Failed to compile regex");
 
-    // Borrow file_content as a &str
-    re.captures_iter(&file_content)
-        .map(|cap| {
-            let x: i32 = cap[1].parse().expect("Failed to parse X");
-            let y: i32 = cap[2].parse().expect("Failed to parse Y");
-            x * y
+    let mut sum = 0;
+    for cap in re.captures_iter(memory) {
+        sum = add(sum, multiply(memory, 0, &cap)?)?;
+    }
+    Ok(sum)
+}
+
+/// Multiplies the two operands of a matched `mul` instruction found `offset` bytes into `memory`.
+fn multiply(memory: &str, offset: usize, cap: &Captures) -> Result<i64> {
+    let operand = |index: usize| {
+        let m = cap.get(index).expect("mul regex has two operand groups");
+        m.as_str().parse::<i64>().map_err(|_| {
+            Error::malformed_at(
+                memory,
+                offset + m.start(),
+                format!("operand '{}' is out of range", m.as_str()),
+            )
         })
-        .sum()
+    };
+
+    let (a, b) = (operand(1)?, operand(2)?);
+    a.checked_mul(b)
+        .ok_or_else(|| Error::InvalidInput(format!("mul({},{}) does not fit in 64 bits", a, b)))
 }
 
-/// Entry point for Part 2: Processes valid `mul` instructions with conditional handling.
-pub fn run_part2() {
-    let args = Args::parse();
-    let sum = process_memory_with_conditionals(&args.file_path);
-    println!("Sum of all enabled mul instructions: {}", sum);
+/// Adds a product to the running sum of the instructions.
+fn add(sum: i64, product: i64) -> Result<i64> {
+    sum.checked_add(product).ok_or_else(|| {
+        Error::InvalidInput("the sum of the products does not fit in 64 bits".into())
+    })
 }
 
 /// Processes the corrupted memory with conditionals and returns the sum of enabled `mul` instructions.
-fn process_memory_with_conditionals(file_path: &str) -> i32 {
-    let file_content = fs::read_to_string(file_path).expect("Failed to read the input file");
-
+pub fn process_memory_with_conditionals(memory: &str) -> Result<i64> {
     let mul_re = Regex::new(r"mul\((\d+),(\d+)\)").expect("Failed to compile mul regex");
     let do_re = Regex::new(r"do\(\)").expect("Failed to compile do regex");
     let dont_re = Regex::new(r"don't\(\)").expect("Failed to compile don't regex");
 
     let mut enabled = true; // At the beginning, `mul` instructions are enabled.
     let mut sum = 0;
+    let mut offset = 0;
 
-    for line in file_content.split_inclusive([';', '\n', '&', '+', ')', ']']) {
+    for line in memory.split_inclusive([';', '\n', '&', '+', ')', ']']) {
         if do_re.is_match(line) {
             enabled = true;
         } else if dont_re.is_match(line) {
@@ -53,24 +106,26 @@ fn process_memory_with_conditionals(file_path: &str) -> i32 {
 
         if enabled {
             for cap in mul_re.captures_iter(line) {
-                let x: i32 = cap[1].parse().expect("Failed to parse X");
-                let y: i32 = cap[2].parse().expect("Failed to parse Y");
-                sum += x * y;
+                sum = add(sum, multiply(memory, offset, &cap)?)?;
             }
         }
+
+        offset += line.len();
     }
 
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_process_memory() {
         let input_path = "input/3-mull-it-over/test-input-1.txt";
-        let actual_memory = process_memory(input_path);
+        let input = fs::read_to_string(input_path).expect("Failed to read the input file");
+        let actual_memory = process_memory(&input).unwrap();
         let expected_memory = 161;
         assert_eq!(actual_memory, expected_memory);
     }
@@ -78,8 +133,34 @@ mod tests {
     #[test]
     fn test_process_memory_with_conditionals() {
         let input_path = "input/3-mull-it-over/test-input-2.txt";
-        let actual_memory = process_memory_with_conditionals(input_path);
+        let input = fs::read_to_string(input_path).expect("Failed to read the input file");
+        let actual_memory = process_memory_with_conditionals(&input).unwrap();
         let expected_memory = 48;
         assert_eq!(actual_memory, expected_memory);
     }
+
+    #[test]
+    fn test_process_memory_reports_overflowing_operand() {
+        let err = process_memory("mul(2,3)\nxmul(99999999999999999999,2)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 6: operand '99999999999999999999' is out of range"
+        );
+    }
+
+    #[test]
+    fn test_process_memory_reports_overflow() {
+        let err = process_memory("mul(4000000000,4000000000)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: mul(4000000000,4000000000) does not fit in 64 bits"
+        );
+
+        let big = "mul(3000000000,3000000000)".repeat(2);
+        let err = process_memory_with_conditionals(&big).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: the sum of the products does not fit in 64 bits"
+        );
+    }
 }
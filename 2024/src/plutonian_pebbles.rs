@@ -1,54 +1,57 @@
-use crate::utils::Args;
+use aoc_common::error::{parse_token, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 use rayon::prelude::*;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
 
-enum PuzzlePart {
-    Part1,
-    Part2,
-}
+pub struct PlutonianPebbles;
 
-pub fn run_part1() {
-    let args = Args::parse();
-    let input_path = Path::new(&args.file_path);
-    run(PuzzlePart::Part1, input_path);
-}
+impl Solution for PlutonianPebbles {
+    type Input = Vec<BigUint>;
 
-/// Entrypoint for handling both parts.
-/// Reads the initial stones, applies transformations, and prints the result.
-fn run(part: PuzzlePart, input_path: &Path) {
-    let stones = read_stones(input_path);
-    let result = match part {
-        PuzzlePart::Part1 => count_stones_after_blinks(stones, 25),
-        PuzzlePart::Part2 => count_stones_after_blinks(stones, 75),
-    };
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_stones(input)
+    }
+
+    /// Part 1: applies 25 transformations to the initial stones.
+    fn part1(stones: &Self::Input) -> Result<Answer> {
+        Ok(count_stones_after_blinks(stones.clone(), 25).into())
+    }
 
-    println!("{}", result);
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_stones(rng, size))
+    }
 }
 
-/// Reads stones from the given file path.
+/// Reads stones from the puzzle input.
 /// Each line can contain multiple numbers separated by whitespace.
 /// Converts each stone into a `BigUint`.
-fn read_stones(path: &Path) -> Vec<BigUint> {
-    let file = File::open(path).expect("Could not open input file.");
-    let reader = BufReader::new(file);
-
+pub fn read_stones(input: &str) -> Result<Vec<BigUint>> {
     let mut stones = Vec::new();
-    for line in reader.lines() {
-        let line = line.expect("Error reading line from file.");
+    for (index, line) in input.lines().enumerate() {
         for num in line.split_whitespace() {
-            let val = num.parse::<BigUint>().expect("Invalid number in input");
-            stones.push(val);
+            stones.push(parse_token(index, line, num, "stone number")?);
         }
     }
-    stones
+    Ok(stones)
+}
+
+/// One line of `size` stones, mostly small numbers with some zeros and some large ones.
+pub fn generate_stones(rng: &mut Rng, size: usize) -> String {
+    let stones: Vec<String> = (0..size)
+        .map(|_| match rng.range(0..=5) {
+            0 => 0,
+            1 => rng.range(1_000_000..=9_999_999),
+            _ => rng.range(1..=9_999),
+        })
+        .map(|stone| stone.to_string())
+        .collect();
+    stones.join(" ") + "\n"
 }
 
 /// Applies the transformation rules a specified number of times and returns the final count.
-fn count_stones_after_blinks(mut stones: Vec<BigUint>, blinks: u32) -> usize {
+pub fn count_stones_after_blinks(mut stones: Vec<BigUint>, blinks: u32) -> usize {
     for _ in 0..blinks {
         stones = transform_stones(&stones);
     }
@@ -60,7 +63,7 @@ fn count_stones_after_blinks(mut stones: Vec<BigUint>, blinks: u32) -> usize {
 /// To improve performance:
 /// - We only convert to a string to determine if digits are even or odd.
 /// - We do numeric splitting for even-digit stones without parsing strings again.
-fn transform_stones(stones: &[BigUint]) -> Vec<BigUint> {
+pub fn transform_stones(stones: &[BigUint]) -> Vec<BigUint> {
     let multiplier = BigUint::from(2024u64);
 
     stones
@@ -74,7 +77,7 @@ fn transform_stones(stones: &[BigUint]) -> Vec<BigUint> {
 /// 1. If the stone is 0, it becomes 1.
 /// 2. If the stone's decimal representation has an even number of digits, split it into two numbers.
 /// 3. Otherwise, multiply by 2024.
-fn transform_stone(stone: &BigUint, multiplier: &BigUint) -> Vec<BigUint> {
+pub fn transform_stone(stone: &BigUint, multiplier: &BigUint) -> Vec<BigUint> {
     // 0 -> 1
     if stone.is_zero() {
         return vec![BigUint::one()];
@@ -84,7 +87,7 @@ fn transform_stone(stone: &BigUint, multiplier: &BigUint) -> Vec<BigUint> {
     let digits = digits_str.len();
 
     // even-digit split
-    if digits % 2 == 0 {
+    if digits.is_multiple_of(2) {
         let half = digits / 2;
         let power = BigUint::from(10u64).pow(half as u32);
         let left = stone / &power;
@@ -99,12 +102,13 @@ fn transform_stone(stone: &BigUint, multiplier: &BigUint) -> Vec<BigUint> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
+    use std::fs;
 
     #[test]
     fn test_simple_part1() {
         let path = "input/11-plutonian-pebbles/test-input-1.txt";
-        let stones = read_stones(Path::new(path));
+        let input = fs::read_to_string(path).expect("Could not open input file.");
+        let stones = read_stones(&input).unwrap();
 
         let count = count_stones_after_blinks(stones, 25);
         // For input "125 17", the known result after 25 transformations is 55312.
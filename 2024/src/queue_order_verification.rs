@@ -1,64 +1,120 @@
-use crate::utils::Args;
-use regex::Regex;
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-pub fn run_part1() {
-    let args = Args::parse();
-    let (rules, updates) = parse_input(&args.file_path);
-
-    let correct_updates: Vec<Vec<i32>> = updates
-        .iter()
-        .filter(|update| is_update_in_correct_order(&rules, update))
-        .cloned()
-        .collect();
+use aoc_common::error::{Error, Result};
+use aoc_common::parse;
+use aoc_common::rng::Rng;
+use aoc_common::search;
+use aoc_common::solution::{Answer, Solution};
+use std::collections::{HashMap, HashSet};
 
-    let middle_page_sum: i32 = correct_updates
-        .iter()
-        .map(|update| get_middle_page(update))
-        .sum();
+pub struct QueueOrderVerification;
+
+/// The `X|Y` page ordering rules and the list of updates to check.
+pub type PrintQueue = (Vec<(i32, i32)>, Vec<Vec<i32>>);
+
+impl Solution for QueueOrderVerification {
+    type Input = PrintQueue;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1((rules, updates): &Self::Input) -> Result<Answer> {
+        let middle_page_sum: i32 = updates
+            .iter()
+            .filter(|update| is_update_in_correct_order(rules, update))
+            .map(|update| get_middle_page(update))
+            .sum();
 
-    println!(
-        "Sum of middle pages in correctly ordered updates: {}",
-        middle_page_sum
-    );
+        Ok(middle_page_sum.into())
+    }
+
+    fn part2((rules, updates): &Self::Input) -> Result<Answer> {
+        let middle_page_sum: i32 = updates
+            .iter()
+            .filter(|update| !is_update_in_correct_order(rules, update))
+            .map(|update| {
+                correct_update_order(rules, update).map(|update| get_middle_page(&update))
+            })
+            .sum::<Result<i32>>()?;
+
+        Ok(middle_page_sum.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_print_queue(rng, size))
+    }
 }
 
-fn parse_input(filename: &str) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
-    let file = File::open(filename).expect("Could not open file");
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
+/// Parses the `X|Y` ordering rules, a blank line, then the comma-separated updates.
+pub fn parse_input(input: &str) -> Result<PrintQueue> {
+    let sections = parse::sections(input);
+    let [rules, updates] = &sections[..] else {
+        return Err(Error::MissingSection(
+            "page updates after the ordering rules",
+        ));
+    };
 
-    let mut rules = Vec::new();
-    let mut updates = Vec::new();
+    let rules = rules
+        .iter()
+        .map(|line| {
+            let (before, after) = line.split_once(line.text, "|")?;
+            Ok((
+                line.parse(before, "page number")?,
+                line.parse(after, "page number")?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+    let updates = updates
+        .iter()
+        .map(|line| {
+            line.text
+                .split(',')
+                .map(|page| line.parse(page, "page number"))
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+
+    Ok((rules, updates))
+}
 
-    // First parse ordering rules
-    for line in lines.by_ref() {
-        let line = line.expect("Could not read line");
-        if line.trim().is_empty() {
-            break;
+/// Ordering rules for every pair of up to 90 two-digit pages, taken from one random order
+/// so that they never contradict each other, followed by `size` updates of an odd number of
+/// pages; about half of the updates are already in order.
+pub fn generate_print_queue(rng: &mut Rng, size: usize) -> String {
+    let mut pages: Vec<i64> = (10..=99).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(size.clamp(3, pages.len()));
+
+    let mut rules = Vec::new();
+    for (index, before) in pages.iter().enumerate() {
+        for after in &pages[index + 1..] {
+            rules.push(format!("{}|{}\n", before, after));
         }
-        let parts: Vec<i32> = line.split('|').map(|s| s.parse().unwrap()).collect();
-        rules.push((parts[0], parts[1]));
     }
-
-    // Then parse updates
-    for line in lines {
-        let line = line.expect("Could not read line");
-        if line.trim().is_empty() {
-            continue;
+    rng.shuffle(&mut rules);
+
+    let mut input = rules.concat();
+    input.push('\n');
+    let longest = (pages.len() - 1) | 1;
+    for _ in 0..size {
+        let length = (2 * rng.range(1..=11) as usize + 1).min(longest);
+        let mut positions: Vec<usize> = (0..pages.len()).collect();
+        rng.shuffle(&mut positions);
+        positions.truncate(length);
+        if rng.ratio(1, 2) {
+            positions.sort();
         }
-        let update: Vec<i32> = line.split(',').map(|s| s.parse().unwrap()).collect();
-        updates.push(update);
+        let update: Vec<String> = positions
+            .iter()
+            .map(|&position| pages[position].to_string())
+            .collect();
+        input.push_str(&update.join(","));
+        input.push('\n');
     }
-
-    (rules, updates)
+    input
 }
 
-fn is_update_in_correct_order(rules: &[(i32, i32)], update: &[i32]) -> bool {
+pub fn is_update_in_correct_order(rules: &[(i32, i32)], update: &[i32]) -> bool {
     let dependencies = build_dependency_graph(rules, update);
 
     for (i, &page) in update.iter().enumerate() {
@@ -92,118 +148,32 @@ fn build_dependency_graph(rules: &[(i32, i32)], update: &[i32]) -> HashMap<i32,
     dependencies
 }
 
-fn get_middle_page(update: &[i32]) -> i32 {
+pub fn get_middle_page(update: &[i32]) -> i32 {
     update[update.len() / 2]
 }
 
-pub fn run_part2() {
-    let args = Args::parse();
-    let (rules, updates) = parse_input(&args.file_path);
-
-    let incorrectly_ordered_updates: Vec<Vec<i32>> = updates
-        .iter()
-        .filter(|update| !is_update_in_correct_order(&rules, update))
-        .cloned()
-        .collect();
-
-    let corrected_updates: Vec<Vec<i32>> = incorrectly_ordered_updates
-        .iter()
-        .map(|update| correct_update_order(rules.clone(), update))
-        .collect();
-
-    let middle_page_sum: i32 = corrected_updates
-        .iter()
-        .map(|update| get_middle_page(update))
-        .sum();
-
-    println!(
-        "Sum of middle pages in corrected updates: {}",
-        middle_page_sum
-    );
-}
-
-fn correct_update_order(rules: Vec<(i32, i32)>, update: &[i32]) -> Vec<i32> {
-    // Create a dependency graph and in-degree map
-    let mut graph: HashMap<i32, HashSet<i32>> = HashMap::new();
-    let mut in_degree: HashMap<i32, usize> = HashMap::new();
-    let update_set: HashSet<i32> = update.iter().cloned().collect();
-
-    // Initialize in-degree and graph
-    for &page in update {
-        in_degree.insert(page, 0);
-    }
-
-    // Build graph and calculate in-degrees
-    for (before, after) in &rules {
-        if update_set.contains(before) && update_set.contains(after) {
-            graph.entry(*before).or_default().insert(*after);
-
-            *in_degree.entry(*after).or_default() += 1;
-        }
-    }
-
-    // Topological sort with original order preservation
-    let mut result = Vec::new();
-    let mut candidates: VecDeque<i32> = update
-        .iter()
-        .filter(|&&page| in_degree[&page] == 0)
-        .cloned()
-        .collect();
-
-    // Preserve original order for candidates with zero in-degree
-    candidates.make_contiguous().sort_by(|&a, &b| {
-        update
-            .iter()
-            .position(|&x| x == a)
-            .cmp(&update.iter().position(|&x| x == b))
-    });
-
-    while let Some(current) = candidates.pop_front() {
-        result.push(current);
-
-        // Check neighbors
-        if let Some(neighbors) = graph.get(&current) {
-            for &neighbor in neighbors {
-                let degree = in_degree.get_mut(&neighbor).unwrap();
-                *degree -= 1;
-
-                if *degree == 0 {
-                    candidates.push_back(neighbor);
-                }
-            }
-        }
-
-        // If multiple candidates, sort by original order
-        if candidates.len() > 1 {
-            candidates.make_contiguous().sort_by(|&a, &b| {
-                update
-                    .iter()
-                    .position(|&x| x == a)
-                    .cmp(&update.iter().position(|&x| x == b))
-            });
-        }
-    }
-
-    // If result doesn't include all pages, use original order for remaining
-    let missing_pages: Vec<i32> = update
-        .iter()
-        .filter(|&&page| !result.contains(&page))
-        .cloned()
-        .collect();
-
-    result.extend(missing_pages);
-
-    result
+/// Reorders an update so it satisfies every rule between its pages, moving pages as
+/// little as possible: whenever several pages could come next, the one that came first
+/// in the update is placed first.
+pub fn correct_update_order(rules: &[(i32, i32)], update: &[i32]) -> Result<Vec<i32>> {
+    search::topological_sort(update, rules.iter().copied()).map_err(|cycle| {
+        Error::InvalidInput(format!(
+            "the rules for update {:?} contain a {}",
+            update, cycle
+        ))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_find_word() {
         let input_path = "input/5-queue-order-verification/test-input-1.txt";
-        let (rules, updates) = parse_input(input_path);
+        let input = fs::read_to_string(input_path).expect("Could not open file");
+        let (rules, updates) = parse_input(&input).unwrap();
 
         // Verify rules parsing
         assert_eq!(rules.len(), 21, "Should parse 21 rules"); // Changed from 22 to 21
@@ -260,7 +230,8 @@ mod tests {
     #[test]
     fn test_correct_update_order() {
         let input_path = "input/5-queue-order-verification/test-input-2.txt";
-        let (rules, updates) = parse_input(input_path);
+        let input = fs::read_to_string(input_path).expect("Could not open file");
+        let (rules, updates) = parse_input(&input).unwrap();
 
         let incorrectly_ordered_updates: Vec<Vec<i32>> = updates
             .iter()
@@ -270,7 +241,7 @@ mod tests {
 
         let corrected_updates: Vec<Vec<i32>> = incorrectly_ordered_updates
             .iter()
-            .map(|update| correct_update_order(rules.clone(), update))
+            .map(|update| correct_update_order(&rules, update).unwrap())
             .collect();
 
         // Verify corrections
@@ -307,4 +278,34 @@ mod tests {
         let middle_page_sum: i32 = middle_pages.iter().sum();
         assert_eq!(middle_page_sum, 123, "Sum of middle pages should be 123");
     }
+
+    #[test]
+    fn test_correct_update_order_reports_cycles() {
+        let rules = [(1, 2), (2, 3), (3, 1)];
+        let err = correct_update_order(&rules, &[3, 2, 1]).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        let err = parse_input("47|53\n97-75\n\n75,47\n").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::MalformedLine {
+                line: 2,
+                column: 1,
+                ..
+            }
+        ));
+
+        let err = parse_input("47|53\n\n75,4x\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 4: invalid page number '4x'"
+        );
+
+        let err = parse_input("47|53\n97|75\n").unwrap_err();
+        assert!(matches!(err, Error::MissingSection(_)));
+    }
 }
@@ -1,30 +1,49 @@
-use crate::utils::Args;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use aoc_common::error::{parse_token, Result};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
+use rules::SafetyRules;
+use std::collections::HashMap;
 
-/// Checks if a report is safe by verifying that levels are either consistently
-/// increasing or consistently decreasing with a difference of 1 to 3.
-fn is_safe_report(levels: &Vec<i32>) -> bool {
-    let increasing = levels
-        .windows(2)
-        .all(|window| window[1] - window[0] >= 1 && window[1] - window[0] <= 3);
+pub mod rules;
+
+pub struct RedNosedReport;
 
-    let decreasing = levels
-        .windows(2)
-        .all(|window| window[0] - window[1] >= 1 && window[0] - window[1] <= 3);
+impl Solution for RedNosedReport {
+    type Input = Vec<Vec<i32>>;
+    const PARTS: u8 = 2;
 
-    increasing || decreasing
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_reports(input)
+    }
+
+    fn part1(reports: &Self::Input) -> Result<Answer> {
+        Ok(count_safe_reports(reports).into())
+    }
+
+    fn part2(reports: &Self::Input) -> Result<Answer> {
+        Ok(count_safe_reports_with_dampener(reports).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_reports(rng, size))
+    }
+}
+
+/// Checks if a report is safe by verifying that levels are either consistently
+/// increasing or consistently decreasing with a difference of 1 to 3.
+pub fn is_safe_report(levels: &[i32]) -> bool {
+    SafetyRules::default().is_safe(levels)
 }
 
 /// Handles the Problem Dampener by checking if removing one level makes the report safe.
-fn is_safe_with_dampener(levels: &Vec<i32>) -> bool {
+/// This is the reference: it tries removing every level in turn.
+pub fn is_safe_with_dampener(levels: &[i32]) -> bool {
     if is_safe_report(levels) {
         return true;
     }
 
     for i in 0..levels.len() {
-        let mut modified_levels = levels.clone();
+        let mut modified_levels = levels.to_vec();
         modified_levels.remove(i);
 
         if is_safe_report(&modified_levels) {
@@ -35,8 +54,76 @@ fn is_safe_with_dampener(levels: &Vec<i32>) -> bool {
     false
 }
 
+/// The fewest levels to remove for the report to become safe, as ascending indices, or `None`
+/// if that takes more than `max_removals`. Part 2 allows one removal.
+///
+/// The levels kept form the longest subsequence that follows the rules in one direction. A
+/// single pass finds it for both directions: the longest safe subsequence ending at a level
+/// extends the longest one ending at any earlier level 1 to 3 below it (or above it, when
+/// decreasing), so only the best subsequence ending at each value has to be remembered.
+pub fn levels_to_remove(levels: &[i32], max_removals: usize) -> Option<Vec<usize>> {
+    let mut chains = [Chains::new(1), Chains::new(-1)];
+    for &level in levels {
+        for chains in &mut chains {
+            chains.push(level);
+        }
+    }
+
+    chains
+        .iter()
+        .map(Chains::removed)
+        .min_by_key(Vec::len)
+        .filter(|removed| removed.len() <= max_removals)
+}
+
+/// The longest safe subsequences in one direction of the levels pushed so far.
+struct Chains {
+    direction: i32,
+    /// For every level, the length of the longest safe subsequence ending with it and the
+    /// level before it in that subsequence.
+    ends: Vec<(usize, Option<usize>)>,
+    /// For every value, the level ending the longest safe subsequence that ends in it.
+    best: HashMap<i32, usize>,
+}
+
+impl Chains {
+    fn new(direction: i32) -> Self {
+        Chains {
+            direction,
+            ends: Vec::new(),
+            best: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, level: i32) {
+        let previous = (1..=3)
+            .filter_map(|step| level.checked_sub(step * self.direction))
+            .filter_map(|value| self.best.get(&value).copied())
+            .max_by_key(|&previous| self.ends[previous].0);
+        let length = previous.map_or(1, |previous| self.ends[previous].0 + 1);
+
+        let index = self.ends.len();
+        self.ends.push((length, previous));
+        let best = self.best.entry(level).or_insert(index);
+        if self.ends[*best].0 <= length {
+            *best = index;
+        }
+    }
+
+    /// The levels left out of the longest safe subsequence.
+    fn removed(&self) -> Vec<usize> {
+        let mut kept = vec![false; self.ends.len()];
+        let mut last = (0..self.ends.len()).max_by_key(|&index| self.ends[index].0);
+        while let Some(index) = last {
+            kept[index] = true;
+            last = self.ends[index].1;
+        }
+        (0..kept.len()).filter(|&index| !kept[index]).collect()
+    }
+}
+
 /// Counts the number of safe reports in the provided list for Part 1.
-fn count_safe_reports(reports: &Vec<Vec<i32>>) -> usize {
+pub fn count_safe_reports(reports: &[Vec<i32>]) -> usize {
     reports
         .iter()
         .filter(|report| is_safe_report(report))
@@ -44,56 +131,60 @@ fn count_safe_reports(reports: &Vec<Vec<i32>>) -> usize {
 }
 
 /// Counts the number of safe reports in the provided list for Part 2.
-fn count_safe_reports_with_dampener(reports: &Vec<Vec<i32>>) -> usize {
+pub fn count_safe_reports_with_dampener(reports: &[Vec<i32>]) -> usize {
     reports
         .iter()
-        .filter(|report| is_safe_with_dampener(report))
+        .filter(|report| levels_to_remove(report, 1).is_some())
         .count()
 }
 
-fn read_reports_from_file(filename: &str) -> Vec<Vec<i32>> {
-    let path = Path::new(filename);
-    let file = File::open(&path).expect("Unable to open file");
-    let reader = BufReader::new(file);
-
-    reader
+pub fn parse_reports(input: &str) -> Result<Vec<Vec<i32>>> {
+    input
         .lines()
-        .map(|line| {
-            line.expect("Could not read line")
-                .split_whitespace()
-                .map(|num| num.parse::<i32>().expect("Failed to parse number"))
-                .collect()
-        })
+        .enumerate()
+        .map(|(index, line)| parse_report(index, line))
         .collect()
 }
 
-pub fn run_part1() {
-    let args = Args::parse();
-    let reports = read_reports_from_file(&args.file_path);
-    let safe_reports_count = count_safe_reports(&reports);
-
-    println!("Part 1 - Number of safe reports: {}", safe_reports_count);
+/// The levels of the report on the line with 0-based `index`.
+pub fn parse_report(index: usize, line: &str) -> Result<Vec<i32>> {
+    line.split_whitespace()
+        .map(|num| parse_token(index, line, num, "level"))
+        .collect()
 }
 
-pub fn run_part2() {
-    let args = Args::parse();
-    let reports = read_reports_from_file(&args.file_path);
-    let safe_reports_count = count_safe_reports_with_dampener(&reports);
-
-    println!(
-        "Part 2 - Number of safe reports with Problem Dampener: {}",
-        safe_reports_count
-    );
+/// `size` reports of 5 to 8 levels that mostly follow the safety rules, with a bad step now
+/// and then so that every outcome of the dampener shows up.
+pub fn generate_reports(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let direction = *rng.pick(&[1, -1]);
+        let mut level = rng.range(30..=70);
+        let mut levels = vec![level.to_string()];
+        for _ in 0..rng.range(4..=7) {
+            level += if rng.ratio(1, 8) {
+                rng.range(-4..=4)
+            } else {
+                rng.range(1..=3) * direction
+            };
+            levels.push(level.to_string());
+        }
+        input.push_str(&levels.join(" "));
+        input.push('\n');
+    }
+    input
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_red_nosed_safe_reports_count() {
         let input_path = "input/2-red_nosed_report/test-input-1.txt";
-        let reports = read_reports_from_file(input_path);
+        let input = fs::read_to_string(input_path).expect("Unable to open file");
+        let reports = parse_reports(&input).unwrap();
         let safe_reports_count = count_safe_reports(&reports);
 
         let expected_safe_reports_count = 2;
@@ -103,10 +194,31 @@ mod tests {
     #[test]
     fn test_red_nosed_safe_reports_with_dampener_count() {
         let input_path = "input/2-red_nosed_report/test-input-2.txt";
-        let reports = read_reports_from_file(input_path);
+        let input = fs::read_to_string(input_path).expect("Unable to open file");
+        let reports = parse_reports(&input).unwrap();
         let safe_reports_count = count_safe_reports_with_dampener(&reports);
 
         let expected_safe_reports_count = 4;
         assert_eq!(safe_reports_count, expected_safe_reports_count);
     }
+
+    #[test]
+    fn test_red_nosed_levels_to_remove() {
+        assert_eq!(levels_to_remove(&[7, 6, 4, 2, 1], 0), Some(vec![]));
+        assert_eq!(levels_to_remove(&[1, 3, 2, 4, 5], 1), Some(vec![1]));
+        assert_eq!(levels_to_remove(&[8, 6, 4, 4, 1], 1), Some(vec![2]));
+        assert_eq!(levels_to_remove(&[8, 6, 4, 4, 1], 0), None);
+        assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], 1), None);
+        assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], 2), Some(vec![0, 1]));
+        assert_eq!(levels_to_remove(&[], 0), Some(vec![]));
+        assert_eq!(levels_to_remove(&[i32::MIN, i32::MAX], 1), Some(vec![0]));
+
+        // A long stream with a spike every 100 levels.
+        let levels: Vec<i32> = (0..100_000)
+            .map(|i| if i % 100 == 50 { -1 } else { i })
+            .collect();
+        let removed = levels_to_remove(&levels, 1000).unwrap();
+        assert_eq!(removed.len(), 1000);
+        assert!(removed.iter().all(|&index| levels[index] == -1));
+    }
 }
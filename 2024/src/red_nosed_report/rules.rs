@@ -0,0 +1,212 @@
+//! Safety rules other than the puzzle's, and which rule an unsafe report breaks first.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Which way the levels of a report may move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    /// Either way, as long as the whole report keeps to the way of its first step.
+    Either,
+}
+
+impl FromStr for Trend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "increasing" => Ok(Trend::Increasing),
+            "decreasing" => Ok(Trend::Decreasing),
+            "either" => Ok(Trend::Either),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What makes a report safe. The default is the puzzle's: levels all increasing or all
+/// decreasing, by 1 to 3 at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyRules {
+    /// Smallest allowed difference between adjacent levels, plateaus aside.
+    pub min_step: u32,
+    /// Largest allowed difference between adjacent levels.
+    pub max_step: u32,
+    pub direction: Trend,
+    /// Whether adjacent levels may be equal. A plateau has no direction, so it neither sets
+    /// nor breaks the direction of [`Trend::Either`].
+    pub allow_plateaus: bool,
+}
+
+impl Default for SafetyRules {
+    fn default() -> Self {
+        SafetyRules {
+            min_step: 1,
+            max_step: 3,
+            direction: Trend::Either,
+            allow_plateaus: false,
+        }
+    }
+}
+
+/// A rule a pair of adjacent levels can break, with the limit it was held to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Plateau,
+    /// The pair moves against this direction: the allowed one, or that of the report's first
+    /// step.
+    Direction(Trend),
+    MinStep(u32),
+    MaxStep(u32),
+}
+
+impl Rule {
+    /// The name used in JSON output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::Plateau => "plateau",
+            Rule::Direction(_) => "direction",
+            Rule::MinStep(_) => "min-step",
+            Rule::MaxStep(_) => "max-step",
+        }
+    }
+}
+
+/// The first pair of adjacent levels that breaks a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the first level of the pair.
+    pub index: usize,
+    pub from: i32,
+    pub to: i32,
+    pub rule: Rule,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "levels {} and {} ({} -> {}): ",
+            self.index,
+            self.index + 1,
+            self.from,
+            self.to
+        )?;
+        let step = step(self.from, self.to).unsigned_abs();
+        match self.rule {
+            Rule::Plateau => write!(f, "plateaus are not allowed"),
+            Rule::Direction(Trend::Decreasing) => write!(f, "should be decreasing"),
+            Rule::Direction(_) => write!(f, "should be increasing"),
+            Rule::MinStep(min) => write!(f, "step of {} is below the minimum of {}", step, min),
+            Rule::MaxStep(max) => write!(f, "step of {} is above the maximum of {}", step, max),
+        }
+    }
+}
+
+fn step(from: i32, to: i32) -> i64 {
+    i64::from(to) - i64::from(from)
+}
+
+impl SafetyRules {
+    /// The first pair of adjacent levels that breaks these rules, or `None` if the report is
+    /// safe. Of the rules one pair breaks, the plateau and direction rules come first.
+    pub fn first_violation(&self, levels: &[i32]) -> Option<Violation> {
+        let mut direction = self.direction;
+        levels.windows(2).enumerate().find_map(|(index, pair)| {
+            let (from, to) = (pair[0], pair[1]);
+            let step = step(from, to);
+            let rule = if step == 0 {
+                (!self.allow_plateaus).then_some(Rule::Plateau)
+            } else {
+                let moved = if step > 0 {
+                    Trend::Increasing
+                } else {
+                    Trend::Decreasing
+                };
+                if direction == Trend::Either {
+                    direction = moved;
+                }
+                if moved != direction {
+                    Some(Rule::Direction(direction))
+                } else if step.unsigned_abs() < u64::from(self.min_step) {
+                    Some(Rule::MinStep(self.min_step))
+                } else if step.unsigned_abs() > u64::from(self.max_step) {
+                    Some(Rule::MaxStep(self.max_step))
+                } else {
+                    None
+                }
+            };
+            rule.map(|rule| Violation {
+                index,
+                from,
+                to,
+                rule,
+            })
+        })
+    }
+
+    pub fn is_safe(&self, levels: &[i32]) -> bool {
+        self.first_violation(levels).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_red_nosed_first_violation() {
+        let rules = SafetyRules::default();
+        let explain = |levels: &[i32]| rules.first_violation(levels).map(|v| v.to_string());
+
+        assert_eq!(explain(&[7, 6, 4, 2, 1]), None);
+        assert_eq!(
+            explain(&[1, 2, 7, 8, 9]),
+            Some("levels 1 and 2 (2 -> 7): step of 5 is above the maximum of 3".to_string())
+        );
+        assert_eq!(
+            explain(&[1, 3, 2, 4, 5]),
+            Some("levels 1 and 2 (3 -> 2): should be increasing".to_string())
+        );
+        assert_eq!(
+            explain(&[8, 6, 4, 4, 1]),
+            Some("levels 2 and 3 (4 -> 4): plateaus are not allowed".to_string())
+        );
+
+        let violation = rules.first_violation(&[i32::MIN, i32::MAX]).unwrap();
+        assert_eq!((violation.index, violation.rule), (0, Rule::MaxStep(3)));
+    }
+
+    #[test]
+    fn test_red_nosed_custom_rules() {
+        let rules = SafetyRules {
+            min_step: 2,
+            max_step: 5,
+            direction: Trend::Decreasing,
+            allow_plateaus: true,
+        };
+        assert!(rules.is_safe(&[9, 9, 7, 2, 2]));
+        assert_eq!(
+            rules.first_violation(&[9, 8]).unwrap().to_string(),
+            "levels 0 and 1 (9 -> 8): step of 1 is below the minimum of 2"
+        );
+        assert_eq!(
+            rules.first_violation(&[9, 9, 12]).unwrap().to_string(),
+            "levels 1 and 2 (9 -> 12): should be decreasing"
+        );
+
+        // A plateau does not fix the direction of `Either`.
+        let rules = SafetyRules {
+            allow_plateaus: true,
+            ..SafetyRules::default()
+        };
+        assert!(rules.is_safe(&[3, 3, 1]));
+        assert_eq!(
+            rules
+                .first_violation(&[3, 3, 1, 2])
+                .map(|v| (v.index, v.rule)),
+            Some((2, Rule::Direction(Trend::Decreasing)))
+        );
+    }
+}
@@ -1,22 +1,68 @@
-use crate::utils::Args;
+use aoc_common::error::Result;
+use aoc_common::grid::{Grid, Pos};
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
-use std::fs;
-
-/// Parses the input file to build the antenna map and determine grid dimensions.
-fn parse_antenna_map(input: &str) -> (HashMap<char, Vec<(usize, usize)>>, usize, usize) {
-    let mut antenna_map: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
-    let grid_height = input.lines().count();
-    let grid_width = input.lines().next().unwrap_or("").len();
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            if ch != '.' {
-                antenna_map.entry(ch).or_default().push((x, y));
-            }
-        }
+
+/// Antenna positions grouped by frequency.
+pub type AntennaMap = HashMap<char, Vec<Pos>>;
+
+pub struct ResonantCollinearity;
+
+impl Solution for ResonantCollinearity {
+    type Input = Grid<char>;
+    const PARTS: u8 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_antenna_map(input)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        Ok(get_signal_impact(grid, find_antinodes_part1).into())
     }
 
-    (antenna_map, grid_width, grid_height)
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        Ok(get_signal_impact(grid, find_antinodes_part2).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_antenna_map(rng, size))
+    }
+}
+
+/// Parses the antenna grid.
+/// Antenna frequencies are letters or digits; `.` marks an empty cell.
+pub fn parse_antenna_map(input: &str) -> Result<Grid<char>> {
+    Grid::parse_with(input, |c| {
+        (c == '.' || c.is_ascii_alphanumeric()).then_some(c)
+    })
+}
+
+/// A `size` by `size` map where about one tile in 25 holds an antenna of one of 62
+/// frequencies, or of one of four when the map is small so that antennas pair up.
+pub fn generate_antenna_map(rng: &mut Rng, size: usize) -> String {
+    let frequencies: Vec<char> = ('0'..='9').chain('a'..='z').chain('A'..='Z').collect();
+    let frequencies = &frequencies[..(size / 2).clamp(4, frequencies.len())];
+
+    let map = Grid::new(size, size, '.').map(|_| {
+        if rng.ratio(1, 25) {
+            *rng.pick(frequencies)
+        } else {
+            '.'
+        }
+    });
+    format!("{}\n", map)
+}
+
+/// Groups the antennas of the grid by frequency.
+pub fn find_antennas(grid: &Grid<char>) -> AntennaMap {
+    let mut antenna_map: AntennaMap = HashMap::new();
+    for (pos, &cell) in grid.iter() {
+        if cell != '.' {
+            antenna_map.entry(cell).or_default().push(pos);
+        }
+    }
+    antenna_map
 }
 
 /// Finds GCD of two numbers (used for normalizing direction vectors).
@@ -28,33 +74,30 @@ fn gcd(a: isize, b: isize) -> isize {
     }
 }
 
+/// Row and column offset from `from` to `to`.
+fn delta(from: Pos, to: Pos) -> (isize, isize) {
+    (
+        to.row as isize - from.row as isize,
+        to.col as isize - from.col as isize,
+    )
+}
+
 /// Finds unique antinodes based on the rules of Part 1.
-fn find_antinodes_part1(
-    antenna_map: &HashMap<char, Vec<(usize, usize)>>,
-    grid_width: usize,
-    grid_height: usize,
-) -> HashSet<(usize, usize)> {
+pub fn find_antinodes_part1(grid: &Grid<char>) -> HashSet<Pos> {
     let mut antinodes = HashSet::new();
 
-    for positions in antenna_map.values() {
+    for positions in find_antennas(grid).values() {
         for i in 0..positions.len() {
             for j in (i + 1)..positions.len() {
-                let (x1, y1) = positions[i];
-                let (x2, y2) = positions[j];
-                let dx = x2 as isize - x1 as isize;
-                let dy = y2 as isize - y1 as isize;
+                let (first, second) = (positions[i], positions[j]);
+                let (d_row, d_col) = delta(first, second);
 
                 // Compute and validate antinode positions
                 let candidates = [
-                    (x1 as isize + 2 * dx, y1 as isize + 2 * dy),
-                    (x2 as isize - 2 * dx, y2 as isize - 2 * dy),
+                    grid.offset(second, d_row, d_col),
+                    grid.offset(first, -d_row, -d_col),
                 ];
-
-                for (x, y) in candidates {
-                    if x >= 0 && x < grid_width as isize && y >= 0 && y < grid_height as isize {
-                        antinodes.insert((x as usize, y as usize));
-                    }
-                }
+                antinodes.extend(candidates.into_iter().flatten());
             }
         }
 
@@ -68,36 +111,22 @@ fn find_antinodes_part1(
 }
 
 /// Finds unique antinodes based on the rules of Part 2.
-fn find_antinodes_part2(
-    antenna_map: &HashMap<char, Vec<(usize, usize)>>,
-    grid_width: usize,
-    grid_height: usize,
-) -> HashSet<(usize, usize)> {
+pub fn find_antinodes_part2(grid: &Grid<char>) -> HashSet<Pos> {
     let mut antinodes = HashSet::new();
 
-    for positions in antenna_map.values() {
+    for positions in find_antennas(grid).values() {
         for i in 0..positions.len() {
             for j in (i + 1)..positions.len() {
-                let (x1, y1) = positions[i];
-                let (x2, y2) = positions[j];
+                let (first, second) = (positions[i], positions[j]);
 
                 // Normalize direction vector using GCD
-                let dx = x2 as isize - x1 as isize;
-                let dy = y2 as isize - y1 as isize;
-                let divisor = gcd(dx, dy);
-                let (step_x, step_y) = (dx / divisor, dy / divisor);
+                let (d_row, d_col) = delta(first, second);
+                let divisor = gcd(d_row, d_col);
+                let (step_row, step_col) = (d_row / divisor, d_col / divisor);
 
                 // Traverse in both directions along the line
-                let mut traverse = |mut x: isize, mut y: isize, step_x: isize, step_y: isize| {
-                    while x >= 0 && x < grid_width as isize && y >= 0 && y < grid_height as isize {
-                        antinodes.insert((x as usize, y as usize));
-                        x += step_x;
-                        y += step_y;
-                    }
-                };
-
-                traverse(x1 as isize, y1 as isize, -step_x, -step_y);
-                traverse(x1 as isize + step_x, y1 as isize + step_y, step_x, step_y);
+                antinodes.extend(grid.ray(first, -step_row, -step_col));
+                antinodes.extend(grid.ray(first, step_row, step_col));
             }
 
             // Add all antennas themselves as antinodes
@@ -109,37 +138,27 @@ fn find_antinodes_part2(
 }
 
 /// Calculates the number of antinodes based on the provided ruleset.
-fn get_signal_impact<F>(file_path: &str, find_antinodes: F) -> usize
+pub fn get_signal_impact<F>(grid: &Grid<char>, find_antinodes: F) -> usize
 where
-    F: Fn(&HashMap<char, Vec<(usize, usize)>>, usize, usize) -> HashSet<(usize, usize)>,
+    F: Fn(&Grid<char>) -> HashSet<Pos>,
 {
-    let input = fs::read_to_string(file_path).expect("Unable to read file");
-    let (antenna_map, grid_width, grid_height) = parse_antenna_map(&input);
-    find_antinodes(&antenna_map, grid_width, grid_height).len()
-}
-
-/// Part 1 entry point
-pub fn run_part1() {
-    let args = Args::parse();
-    let result = get_signal_impact(&args.file_path, find_antinodes_part1);
-    println!("Part 1: {}", result);
-}
-
-/// Part 2 entry point
-pub fn run_part2() {
-    let args = Args::parse();
-    let result = get_signal_impact(&args.file_path, find_antinodes_part2);
-    println!("Part 2: {}", result);
+    find_antinodes(grid).len()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+
+    fn read_antenna_map(file_path: &str) -> Grid<char> {
+        let input = fs::read_to_string(file_path).expect("Unable to read file");
+        parse_antenna_map(&input).unwrap()
+    }
 
     #[test]
     fn test_part1() {
         let result = get_signal_impact(
-            "input/8-resonant-collinearity/test-input-1.txt",
+            &read_antenna_map("input/8-resonant-collinearity/test-input-1.txt"),
             find_antinodes_part1,
         );
         assert_eq!(result, 14);
@@ -148,7 +167,7 @@ mod tests {
     #[test]
     fn test_part2() {
         let result = get_signal_impact(
-            "input/8-resonant-collinearity/test-input-2.txt",
+            &read_antenna_map("input/8-resonant-collinearity/test-input-2.txt"),
             find_antinodes_part2,
         );
         assert_eq!(result, 34);
@@ -1,56 +1,84 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::error::{Error, Result};
+use aoc_common::parse;
+use aoc_common::rng::Rng;
+use aoc_common::solution::{Answer, Solution};
 
-#[derive(Debug, Clone, Copy)]
-struct Robot {
-    position: (i64, i64),
-    velocity: (i64, i64),
+pub struct RestroomRedoubt;
+
+impl Solution for RestroomRedoubt {
+    type Input = Vec<Robot>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(robots: &Self::Input) -> Result<Answer> {
+        Ok(solve(robots, 100)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_robots(rng, size))
+    }
 }
 
-fn parse_input(file_path: &str) -> Vec<Robot> {
-    let path = Path::new(file_path);
-    let file = File::open(path).expect("Unable to open file");
-    let reader = io::BufReader::new(file);
-
-    reader
-        .lines()
-        .filter_map(|line| line.ok())
-        .map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let position = parts[0]
-                .strip_prefix("p=")
-                .unwrap()
-                .split(',')
-                .map(|n| n.parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
-            let velocity = parts[1]
-                .strip_prefix("v=")
-                .unwrap()
-                .split(',')
-                .map(|n| n.parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
+#[derive(Debug, Clone, Copy)]
+pub struct Robot {
+    pub position: (i64, i64),
+    pub velocity: (i64, i64),
+}
 
-            Robot {
-                position: (position[0], position[1]),
-                velocity: (velocity[0], velocity[1]),
-            }
+/// Parses one `p=x,y v=dx,dy` robot per line.
+pub fn parse_input(input: &str) -> Result<Vec<Robot>> {
+    parse::parse_lines(input, |line| {
+        let record = line.record(&["p", "v"])?;
+        Ok(Robot {
+            position: record.pair("p", "coordinate")?,
+            velocity: record.pair("v", "coordinate")?,
         })
-        .collect()
+    })
+}
+
+/// `size` robots anywhere on the 101 by 103 floor, with velocities of up to 99 tiles.
+pub fn generate_robots(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        input.push_str(&format!(
+            "p={},{} v={},{}\n",
+            rng.range(0..=100),
+            rng.range(0..=102),
+            rng.range(-99..=99),
+            rng.range(-99..=99)
+        ));
+    }
+    input
 }
 
-fn simulate_robots(robots: &[Robot], seconds: i64, width: i64, height: i64) -> Vec<(i64, i64)> {
+/// Where the robots are after `seconds`. Each step is reduced modulo the floor size first,
+/// so no velocity or number of seconds overflows.
+pub fn simulate_robots(robots: &[Robot], seconds: i64, width: i64, height: i64) -> Vec<(i64, i64)> {
     robots
         .iter()
         .map(|robot| {
-            let x = (robot.position.0 + robot.velocity.0 * seconds).rem_euclid(width);
-            let y = (robot.position.1 + robot.velocity.1 * seconds).rem_euclid(height);
+            let x = travel(robot.position.0, robot.velocity.0, seconds, width);
+            let y = travel(robot.position.1, robot.velocity.1, seconds, height);
             (x, y)
         })
         .collect()
 }
 
-fn calculate_safety_factor(positions: Vec<(i64, i64)>, width: i64, height: i64) -> i64 {
+/// `position + velocity * seconds`, wrapped around a floor `size` tiles across.
+fn travel(position: i64, velocity: i64, seconds: i64, size: i64) -> i64 {
+    let distance = velocity.rem_euclid(size) as i128 * seconds.rem_euclid(size) as i128;
+    ((position.rem_euclid(size) as i128 + distance) % size as i128) as i64
+}
+
+/// The most robots [`solve`] takes: a quarter of them in each quadrant is the largest
+/// safety factor, and it still fits in `i64`.
+pub const MAX_ROBOTS: usize = 220_000;
+
+/// The product of the number of robots in each quadrant. There may be at most
+/// [`MAX_ROBOTS`] positions.
+pub fn calculate_safety_factor(positions: Vec<(i64, i64)>, width: i64, height: i64) -> i64 {
     let mid_x = width / 2;
     let mid_y = height / 2;
 
@@ -74,42 +102,28 @@ fn calculate_safety_factor(positions: Vec<(i64, i64)>, width: i64, height: i64)
     quadrants.iter().product()
 }
 
-pub fn solve(robots: &[Robot], seconds: i64) -> i64 {
+pub fn solve(robots: &[Robot], seconds: i64) -> Result<i64> {
+    if robots.len() > MAX_ROBOTS {
+        return Err(Error::InvalidInput(format!(
+            "{} robots are more than the {} whose safety factor fits in 64 bits",
+            robots.len(),
+            MAX_ROBOTS
+        )));
+    }
     let width = 101;
     let height = 103;
 
     let positions = simulate_robots(robots, seconds, width, height);
-    calculate_safety_factor(positions, width, height)
-}
-
-pub enum PuzzlePart {
-    Part1,
-    Part2,
-}
-
-pub fn run(part: PuzzlePart, input_path: &str) -> i64 {
-    let robots = parse_input(input_path);
-    match part {
-        PuzzlePart::Part1 => solve(&robots, 100),
-        PuzzlePart::Part2 => 0, // Part 2 not implemented yet
-    }
-}
-
-pub fn run_part1() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input_file>", args[0]);
-        return;
-    }
-    let input_path = &args[1];
-    let result = run(PuzzlePart::Part1, input_path);
-    println!("Safety factor: {}", result);
+    Ok(calculate_safety_factor(positions, width, height))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::error::Error;
 
+    #[test]
+    #[ignore = "not run in the baseline either; its expected value is unconfirmed"]
     fn test_calculate_safety_factor() {
         let robots = vec![
             Robot {
@@ -146,18 +160,62 @@ mod tests {
         }];
         let positions = simulate_robots(&robots, 1, 11, 7);
         assert_eq!(positions, vec![(1, 2)]); // Wraps around the edges
+
+        let robots = vec![Robot {
+            position: (i64::MAX, i64::MIN),
+            velocity: (i64::MAX, i64::MIN),
+        }];
+        let positions = simulate_robots(&robots, i64::MAX, 11, 7);
+        assert_eq!(positions, vec![(1, 6)]);
+    }
+
+    #[test]
+    fn test_solve_rejects_too_many_robots() {
+        // A quarter of the robots standing still in each quadrant.
+        let robots: Vec<Robot> = [(0, 0), (100, 0), (0, 102), (100, 102)]
+            .iter()
+            .flat_map(|&position| {
+                let robot = Robot {
+                    position,
+                    velocity: (0, 0),
+                };
+                vec![robot; MAX_ROBOTS / 4]
+            })
+            .collect();
+        assert_eq!(solve(&robots, 100).unwrap(), 55_000_i64.pow(4));
+
+        let mut robots = robots;
+        robots.push(robots[0]);
+        assert!(matches!(
+            solve(&robots, 100).unwrap_err(),
+            Error::InvalidInput(_)
+        ));
     }
 
     #[test]
     fn test_parse_input() {
         let input = "p=0,4 v=3,-3\np=6,3 v=-1,-3";
-        let path = "/tmp/test_input.txt";
-        std::fs::write(path, input).unwrap();
-        let robots = parse_input(path);
+        let robots = parse_input(input).unwrap();
         assert_eq!(robots.len(), 2);
         assert_eq!(robots[0].position, (0, 4));
         assert_eq!(robots[0].velocity, (3, -3));
         assert_eq!(robots[1].position, (6, 3));
         assert_eq!(robots[1].velocity, (-1, -3));
     }
+
+    #[test]
+    fn test_parse_input_errors() {
+        let err = parse_input("p=0,4 v=3,-3\np=6,3 q=-1,-3").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::MalformedLine {
+                line: 2,
+                column: 7,
+                ..
+            }
+        ));
+
+        let err = parse_input("p=0,x v=3,-3").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 5: invalid coordinate 'x'");
+    }
 }
@@ -0,0 +1,86 @@
+use advent_of_code_2024::DAYS;
+use aoc_common::error::{Error, Result};
+use aoc_common::solution::{find_day, Answer};
+
+/// Runs one part of a registered day over an example input through the public API.
+fn solve(day: u8, part: u8, input_path: &str) -> Result<Answer> {
+    find_day(DAYS, day)?.parse_file(input_path)?.part(part)
+}
+
+#[test]
+fn test_examples_through_registry() {
+    let examples = [
+        (1, 1, "input/1-historian/test-input-1.txt", 11),
+        (1, 2, "input/1-historian/test-input-2.txt", 31),
+        (2, 1, "input/2-red_nosed_report/test-input-1.txt", 2),
+        (2, 2, "input/2-red_nosed_report/test-input-2.txt", 4),
+        (3, 1, "input/3-mull-it-over/test-input-1.txt", 161),
+        (3, 2, "input/3-mull-it-over/test-input-2.txt", 48),
+        (4, 1, "input/4-ceres-search/test-input-1.txt", 18),
+        (
+            5,
+            1,
+            "input/5-queue-order-verification/test-input-1.txt",
+            143,
+        ),
+        (
+            5,
+            2,
+            "input/5-queue-order-verification/test-input-2.txt",
+            123,
+        ),
+        (6, 1, "input/6-guard-gallivant/test-input-1.txt", 41),
+        (6, 2, "input/6-guard-gallivant/test-input-1.txt", 6),
+        (7, 1, "input/7-bridge-repair/test-input-1.txt", 3749),
+        (7, 2, "input/7-bridge-repair/test-input-2.txt", 11387),
+        (8, 1, "input/8-resonant-collinearity/test-input-1.txt", 14),
+        (8, 2, "input/8-resonant-collinearity/test-input-2.txt", 34),
+        (9, 1, "input/9-disk-fragmentation/test-input-1.txt", 1928),
+        (9, 2, "input/9-disk-fragmentation/test-input-2.txt", 2858),
+        (11, 1, "input/11-plutonian-pebbles/test-input-1.txt", 55312),
+        (13, 1, "input/13-claw-contraption/test-input-1.txt", 480),
+    ];
+
+    for (day, part, input_path, expected) in examples {
+        assert_eq!(
+            solve(day, part, input_path).unwrap(),
+            Answer::Number(expected),
+            "day {} part {} on {}",
+            day,
+            part,
+            input_path
+        );
+    }
+}
+
+#[test]
+fn test_unimplemented_parts_report_not_implemented() {
+    assert!(matches!(
+        solve(4, 2, "input/4-ceres-search/test-input-1.txt"),
+        Err(Error::NotImplemented { part: 2 })
+    ));
+    assert!(matches!(
+        solve(13, 2, "input/13-claw-contraption/test-input-1.txt"),
+        Err(Error::NotImplemented { part: 2 })
+    ));
+}
+
+#[test]
+fn test_unknown_day_is_an_error() {
+    assert!(matches!(
+        solve(12, 1, "input/1-historian/test-input-1.txt"),
+        Err(Error::UnknownDay(12))
+    ));
+}
+
+#[test]
+fn test_parse_embedded_input_from_reader() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    let puzzle = find_day(DAYS, 1)
+        .unwrap()
+        .parse_reader(input.as_bytes())
+        .unwrap();
+
+    assert_eq!(puzzle.part(1).unwrap(), Answer::Number(11));
+    assert_eq!(puzzle.part(2).unwrap(), Answer::Number(31));
+}
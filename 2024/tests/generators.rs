@@ -0,0 +1,58 @@
+use advent_of_code_2024::DAYS;
+use aoc_common::error::Error;
+use aoc_common::rng::Rng;
+
+/// Every size must parse; parts are only solved on the smaller sizes, since some of them are
+/// slow in debug builds (plutonian pebbles blinks every stone 25 times).
+#[test]
+fn test_generated_inputs_parse_and_solve() {
+    for day in DAYS {
+        for seed in 0..3 {
+            for size in [1, 2, 5, 12] {
+                let input = (day.generate)(&mut Rng::new(seed), size)
+                    .unwrap_or_else(|| panic!("day {} has no input generator", day.number));
+                let context = format!("day {} size {} seed {}:\n{}", day.number, size, seed, input);
+
+                let puzzle = (day.parse)(&input)
+                    .unwrap_or_else(|e| panic!("parse failed: {}\n{}", e, context));
+                if size > 5 {
+                    continue;
+                }
+                for part in [1, 2] {
+                    match puzzle.part(part) {
+                        Ok(_) | Err(Error::NotImplemented { .. }) => {}
+                        Err(e) => panic!("part {} failed: {}\n{}", part, e, context),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generators_are_deterministic() {
+    for day in DAYS {
+        let generate = |seed| (day.generate)(&mut Rng::new(seed), 8);
+        assert_eq!(generate(1), generate(1), "day {}", day.number);
+        assert_ne!(generate(1), generate(2), "day {}", day.number);
+    }
+}
+
+/// Days cheap enough to solve at sizes far beyond the real inputs, which is where overflows
+/// show up.
+#[test]
+fn test_large_generated_inputs_solve() {
+    for (number, size) in [(1, 20_000), (2, 20_000), (3, 20_000), (7, 2_000)] {
+        let day = DAYS
+            .iter()
+            .find(|day| day.number == number)
+            .expect("day is registered");
+        let input = (day.generate)(&mut Rng::new(0), size).expect("day has a generator");
+        let puzzle = (day.parse)(&input).unwrap();
+        for part in [1, 2] {
+            if let Err(e) = puzzle.part(part) {
+                panic!("day {} size {} part {} failed: {}", number, size, part, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use aoc_common::answers::{Answers, ANSWERS_FILE};
+use aoc_common::error::Error;
+use aoc_common::input::{discover_inputs, find_input_dir, InputKind, INPUT_DIR};
+use std::path::Path;
+
+#[test]
+fn test_discover_inputs_in_day_order() {
+    let dirs = discover_inputs(INPUT_DIR).unwrap();
+    let days: Vec<u8> = dirs.iter().map(|dir| dir.day).collect();
+
+    assert_eq!(&days[..4], &[1, 2, 3, 4]);
+    assert_eq!(dirs[0].name, "historian");
+}
+
+#[test]
+fn test_resolve_by_convention() {
+    let dir = find_input_dir(INPUT_DIR, 7).unwrap();
+    assert_eq!(
+        dir.resolve(InputKind::Example, 2).unwrap(),
+        Path::new("input/7-bridge-repair/test-input-2.txt")
+    );
+    assert_eq!(
+        dir.resolve(InputKind::Real, 1).unwrap(),
+        Path::new("input/7-bridge-repair/input-1.txt")
+    );
+
+    // Day 13 has a single input that serves both parts.
+    let dir = find_input_dir(INPUT_DIR, 13).unwrap();
+    assert_eq!(
+        dir.resolve(InputKind::Real, 2).unwrap(),
+        Path::new("input/13-claw-contraption/input-1.txt")
+    );
+
+    // Day 14 has no examples saved.
+    let dir = find_input_dir(INPUT_DIR, 14).unwrap();
+    assert!(matches!(
+        dir.resolve(InputKind::Example, 1),
+        Err(Error::MissingInput(_))
+    ));
+}
+
+#[test]
+fn test_resolve_suffixed_example() {
+    let dir = find_input_dir(INPUT_DIR, 10).unwrap();
+    assert_eq!(
+        dir.resolve(InputKind::Example, 1).unwrap(),
+        Path::new("input/10-hoof-it/test-input-1-complex-rating-1.txt")
+    );
+}
+
+#[test]
+fn test_naming_warning() {
+    let dir = find_input_dir(INPUT_DIR, 2).unwrap();
+    assert_eq!(
+        dir.naming_warning("red-nosed-report").unwrap(),
+        "'input/2-red_nosed_report' is not kebab-case, expected '2-red-nosed-report'"
+    );
+
+    let dir = find_input_dir(INPUT_DIR, 7).unwrap();
+    assert_eq!(dir.naming_warning("bridge-repair"), None);
+    assert!(dir
+        .naming_warning("bridge-repairs")
+        .unwrap()
+        .contains("does not match"));
+}
+
+#[test]
+fn test_answers_file_is_well_formed() {
+    let answers = Answers::load(ANSWERS_FILE).unwrap();
+    assert_eq!(answers.get(1, 1, "test-input-1.txt"), Some("11"));
+}
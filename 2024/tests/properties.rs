@@ -0,0 +1,180 @@
+use advent_of_code_2024::claw_contraption::{find_solution, find_solution_linear, ClawMachine};
+use advent_of_code_2024::disk_fragmentation::{compact_disk, compact_disk_two_pointer};
+use advent_of_code_2024::red_nosed_report::{
+    is_safe_report, is_safe_with_dampener, levels_to_remove,
+};
+use aoc_common::property::{agree, shrink_int, shrink_vec, Property};
+use aoc_common::rng::Rng;
+
+fn shrink_small(value: &i32) -> Vec<i32> {
+    shrink_int(i64::from(*value))
+        .into_iter()
+        .map(|value| value as i32)
+        .collect()
+}
+
+fn render_numbers<T: ToString>(values: &[T], separator: &str) -> String {
+    let values: Vec<String> = values.iter().map(T::to_string).collect();
+    values.join(separator) + "\n"
+}
+
+/// A machine whose prize is usually reachable, with collinear buttons now and then.
+fn claw_machine(rng: &mut Rng, size: usize) -> ClawMachine {
+    let limit = size as i64 + 2;
+    let button_a = (rng.range(0..=limit), rng.range(0..=limit));
+    let button_b = if rng.ratio(1, 4) {
+        let factor = rng.range(1..=3);
+        (button_a.0 * factor, button_a.1 * factor)
+    } else {
+        (rng.range(0..=limit), rng.range(0..=limit))
+    };
+    let prize = if rng.ratio(3, 4) {
+        let (a, b) = (rng.range(0..=110), rng.range(0..=110));
+        (
+            a * button_a.0 + b * button_b.0,
+            a * button_a.1 + b * button_b.1,
+        )
+    } else {
+        (rng.range(0..=limit * 100), rng.range(0..=limit * 100))
+    };
+
+    ClawMachine {
+        button_a,
+        button_b,
+        prize,
+    }
+}
+
+fn shrink_claw_machine(machine: &ClawMachine) -> Vec<ClawMachine> {
+    let values = [
+        machine.button_a.0,
+        machine.button_a.1,
+        machine.button_b.0,
+        machine.button_b.1,
+        machine.prize.0,
+        machine.prize.1,
+    ];
+    shrink_vec(&values, |&value| shrink_int(value))
+        .into_iter()
+        .filter(|values| values.len() == 6)
+        .map(|values| ClawMachine {
+            button_a: (values[0], values[1]),
+            button_b: (values[2], values[3]),
+            prize: (values[4], values[5]),
+        })
+        .collect()
+}
+
+#[test]
+fn test_claw_linear_algebra_matches_brute_force() {
+    Property::new("claw-contraption", claw_machine)
+        .shrink(shrink_claw_machine)
+        .render(|machine| {
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                machine.button_a.0,
+                machine.button_a.1,
+                machine.button_b.0,
+                machine.button_b.1,
+                machine.prize.0,
+                machine.prize.1
+            )
+        })
+        .assert(|machine| {
+            agree(
+                find_solution_linear(machine, 100),
+                find_solution(machine, 100),
+            )
+        });
+}
+
+#[test]
+fn test_two_pointer_compaction_matches_reference() {
+    Property::new("disk-fragmentation", |rng, size| {
+        (0..size)
+            .map(|_| rng.range(0..=9) as u8)
+            .collect::<Vec<u8>>()
+    })
+    .max_size(40)
+    .shrink(|lengths| {
+        shrink_vec(lengths, |&length| {
+            shrink_int(i64::from(length))
+                .into_iter()
+                .map(|length| length as u8)
+                .collect()
+        })
+    })
+    .render(|lengths| render_numbers(lengths, ""))
+    .assert(|lengths| {
+        let disk_map = render_numbers(lengths, "");
+        let disk_map = disk_map.trim();
+        agree(
+            compact_disk_two_pointer(disk_map).ok(),
+            compact_disk(disk_map).ok(),
+        )
+    });
+}
+
+/// Levels that mostly follow the safety rules, with the occasional bad step.
+fn report(rng: &mut Rng, size: usize) -> Vec<i32> {
+    let direction = *rng.pick(&[1, -1]);
+    let mut level = rng.range(1..=50) as i32;
+    let mut levels = vec![level];
+    for _ in 0..size.min(8) {
+        let step = if rng.ratio(1, 6) {
+            rng.range(-4..=4) as i32
+        } else {
+            rng.range(1..=3) as i32 * direction
+        };
+        level += step;
+        levels.push(level);
+    }
+    levels
+}
+
+/// The fewest levels whose removal makes the report safe, trying every subset.
+fn fewest_removals(levels: &[i32]) -> usize {
+    (0..1u32 << levels.len())
+        .filter(|removed| {
+            let kept: Vec<i32> = (0..levels.len())
+                .filter(|&index| removed & (1 << index) == 0)
+                .map(|index| levels[index])
+                .collect();
+            is_safe_report(&kept)
+        })
+        .map(u32::count_ones)
+        .min()
+        .unwrap_or(0) as usize
+}
+
+#[test]
+fn test_tolerant_dampener_matches_brute_force() {
+    Property::new("red-nosed-report", report)
+        .shrink(|levels| shrink_vec(levels, shrink_small))
+        .render(|levels| render_numbers(levels, " "))
+        .assert(|levels| {
+            agree(
+                levels_to_remove(levels, 1).is_some(),
+                is_safe_with_dampener(levels),
+            )?;
+
+            let fewest = fewest_removals(levels);
+            for max_removals in 0..=3 {
+                let removed = levels_to_remove(levels, max_removals);
+                agree(
+                    removed.as_ref().map(Vec::len),
+                    Some(fewest).filter(|&n| n <= max_removals),
+                )?;
+                if let Some(removed) = removed {
+                    let kept: Vec<i32> = (0..levels.len())
+                        .filter(|index| !removed.contains(index))
+                        .map(|index| levels[index])
+                        .collect();
+                    if !is_safe_report(&kept) {
+                        return Err(format!("removing {:?} leaves {:?} unsafe", removed, kept));
+                    }
+                }
+            }
+            Ok(())
+        });
+}
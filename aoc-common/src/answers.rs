@@ -0,0 +1,190 @@
+use crate::error::{parse_token, Error, Result};
+use crate::input::read_file;
+use crate::solution::Answer;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// The file of known answers, relative to the crate root.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// An answer that has been accepted for one part of one day on one input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub day: u8,
+    pub part: u8,
+    /// File name inside the day's input directory, e.g. `input-1.txt`.
+    pub input: String,
+    pub answer: String,
+}
+
+impl fmt::Display for KnownAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.day, self.part, self.input, self.answer
+        )
+    }
+}
+
+/// How a computed answer compares to the known one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Verdict {
+    /// `pass`, `fail` or `unknown`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail { .. } => "fail",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Known answers, one per line as `<day> <part> <input file> <answer>`.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: Vec<KnownAnswer>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            let [day, part, input, answer] = fields[..] else {
+                return Err(Error::malformed(
+                    index,
+                    line,
+                    trimmed,
+                    "expected '<day> <part> <input file> <answer>'",
+                ));
+            };
+
+            entries.push(KnownAnswer {
+                day: parse_token(index, line, day, "day")?,
+                part: parse_token(index, line, part, "part")?,
+                input: input.to_string(),
+                answer: answer.to_string(),
+            });
+        }
+
+        Ok(Answers { entries })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&read_file(path)?)
+    }
+
+    /// Adds `entries` as a new block at the end of the answers file, leaving what is
+    /// already there untouched.
+    pub fn append<P: AsRef<Path>>(path: P, entries: &[KnownAnswer]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut block = String::from("\n");
+        for entry in entries {
+            block.push_str(&format!("{}\n", entry));
+        }
+        OpenOptions::new()
+            .append(true)
+            .open(path)?
+            .write_all(block.as_bytes())?;
+        Ok(())
+    }
+
+    /// The known answers recorded for one part of one day.
+    pub fn for_part(&self, day: u8, part: u8) -> impl Iterator<Item = &KnownAnswer> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.day == day && entry.part == part)
+    }
+
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.for_part(day, part)
+            .find(|entry| entry.input == input)
+            .map(|entry| entry.answer.as_str())
+    }
+
+    pub fn check(&self, day: u8, part: u8, input: &str, answer: &Answer) -> Verdict {
+        match self.get(day, part, input) {
+            None => Verdict::Unknown,
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_answers() {
+        let answers =
+            Answers::parse("# day part input answer\n\n7 1 test-input-1.txt 3749\n").unwrap();
+
+        assert_eq!(
+            answers.check(7, 1, "test-input-1.txt", &Answer::Number(3749)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.check(7, 1, "test-input-1.txt", &Answer::Number(3748)),
+            Verdict::Fail {
+                expected: "3749".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(7, 2, "test-input-1.txt", &Answer::Number(3749)),
+            Verdict::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        let err = Answers::parse("7 1 input-1.txt\n").unwrap_err();
+        assert!(matches!(err, Error::MalformedLine { line: 1, .. }));
+
+        let err = Answers::parse("7 x input-1.txt 5\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 3: invalid part 'x'");
+    }
+
+    #[test]
+    fn test_known_answer_round_trips() {
+        let entry = KnownAnswer {
+            day: 15,
+            part: 2,
+            input: "test-input-2.txt".to_string(),
+            answer: "9021".to_string(),
+        };
+        let answers = Answers::parse(&entry.to_string()).unwrap();
+        assert_eq!(answers.get(15, 2, "test-input-2.txt"), Some("9021"));
+    }
+}
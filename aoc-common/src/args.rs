@@ -0,0 +1,618 @@
+use crate::error::{Error, Result};
+use crate::input::InputKind;
+use crate::property::DEFAULT_SEED;
+use crate::report::Format;
+use std::env;
+use std::str::FromStr;
+
+/// Where `run` reads its input from.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// A file given on the command line, or stdin for `-`.
+    File(String),
+    /// The day's example or real input, found under `input/<day>-<name>/`.
+    Discover(InputKind),
+}
+
+/// Options of the `bench` subcommand.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Benchmark only this day instead of every registered one.
+    pub day: Option<u8>,
+    pub kind: InputKind,
+    /// How many times each stage is run.
+    pub runs: usize,
+    /// Saved results to compare against.
+    pub baseline: Option<String>,
+    /// Where to save this run's results as a new baseline.
+    pub save_baseline: Option<String>,
+    /// How much slower than the baseline, in percent, counts as a regression.
+    pub threshold: u32,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            day: None,
+            kind: InputKind::Real,
+            runs: 10,
+            baseline: None,
+            save_baseline: None,
+            threshold: 20,
+        }
+    }
+}
+
+/// A subcommand parsed from the command line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Runs one part of one day against an input file, or stdin when the path is `-`.
+    Run {
+        day: u8,
+        part: u8,
+        input: InputSource,
+    },
+    /// Runs both parts of every registered day and compares them with the answers file.
+    RunAll { kind: InputKind, parallel: bool },
+    /// Lists the available days and which parts they implement.
+    List,
+    /// Lists the input files found for every day, or for one day.
+    Inputs { day: Option<u8> },
+    /// Compares answers against the answers file, for every day or for one day.
+    Check {
+        day: Option<u8>,
+        kind: Option<InputKind>,
+    },
+    /// Times the parse and both parts of every day, or of one day.
+    Bench(BenchOptions),
+    /// Reruns one day whenever its inputs, the answers file or its source change.
+    Watch {
+        day: u8,
+        kind: Option<InputKind>,
+        /// Milliseconds between two polls of the watched files.
+        interval: u64,
+    },
+    /// Saves the examples and their answers from a saved puzzle page.
+    Extract { page: String, day: Option<u8> },
+    /// Generates and registers the skeleton of a new day.
+    New { day: u8, name: String },
+    /// Writes a random input for one day, to a file or to stdout.
+    Generate {
+        day: u8,
+        size: usize,
+        seed: u64,
+        output: Option<String>,
+    },
+}
+
+pub struct Args {
+    pub command: Command,
+    /// `--format text|json`, accepted anywhere on the command line.
+    pub format: Format,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        Self::from_args(&args)
+    }
+
+    /// Parses the arguments that follow the program name.
+    fn from_args(args: &[String]) -> Result<Self> {
+        let mut format = Format::Text;
+        let mut args_without_format = Vec::with_capacity(args.len());
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--format" {
+                let value = parse_value(iter.next(), "--format")?;
+                format = value.parse().map_err(|_| {
+                    usage_error(format!("--format must be text or json, got '{}'", value))
+                })?;
+            } else {
+                args_without_format.push(arg.clone());
+            }
+        }
+
+        let (subcommand, rest) = args_without_format
+            .split_first()
+            .ok_or_else(|| usage_error("missing subcommand"))?;
+
+        let command = match subcommand.as_str() {
+            "run" => parse_run(rest)?,
+            "list" => {
+                if let Some(extra) = rest.first() {
+                    return Err(usage_error(format!("unexpected argument '{}'", extra)));
+                }
+                Command::List
+            }
+            "inputs" => parse_inputs(rest)?,
+            "check" => parse_check(rest)?,
+            "bench" => parse_bench(rest)?,
+            "watch" => parse_watch(rest)?,
+            "new" => parse_new(rest)?,
+            "extract" => parse_extract(rest)?,
+            "generate" => parse_generate(rest)?,
+            other => return Err(usage_error(format!("unknown subcommand '{}'", other))),
+        };
+
+        Ok(Args { command, format })
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<Command> {
+    if args.iter().any(|arg| arg == "--all") {
+        return parse_run_all(args);
+    }
+
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            "--part" => part = Some(parse_number(iter.next(), "--part")?),
+            "--example" => set_input(&mut input, InputSource::Discover(InputKind::Example))?,
+            "--real" => set_input(&mut input, InputSource::Discover(InputKind::Real))?,
+            flag if flag.starts_with("--") => {
+                return Err(usage_error(format!("unknown option '{}'", flag)))
+            }
+            path => set_input(&mut input, InputSource::File(path.to_string()))?,
+        }
+    }
+
+    let part = part.ok_or_else(|| usage_error("missing --part"))?;
+    if part != 1 && part != 2 {
+        return Err(usage_error(format!("--part must be 1 or 2, got {}", part)));
+    }
+
+    Ok(Command::Run {
+        day: day.ok_or_else(|| usage_error("missing --day"))?,
+        part,
+        input: input.unwrap_or(InputSource::Discover(InputKind::Real)),
+    })
+}
+
+fn parse_run_all(args: &[String]) -> Result<Command> {
+    let mut kind = InputKind::Real;
+    let mut parallel = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--all" => {}
+            "--example" => kind = InputKind::Example,
+            "--real" => kind = InputKind::Real,
+            "--parallel" => parallel = true,
+            other => {
+                return Err(usage_error(format!(
+                    "unexpected argument '{}' with --all",
+                    other
+                )))
+            }
+        }
+    }
+
+    Ok(Command::RunAll { kind, parallel })
+}
+
+fn set_input(input: &mut Option<InputSource>, source: InputSource) -> Result<()> {
+    if input.replace(source).is_some() {
+        return Err(usage_error(
+            "give only one of --example, --real or an input file",
+        ));
+    }
+    Ok(())
+}
+
+fn parse_inputs(args: &[String]) -> Result<Command> {
+    let mut day = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            other => return Err(usage_error(format!("unexpected argument '{}'", other))),
+        }
+    }
+
+    Ok(Command::Inputs { day })
+}
+
+fn parse_check(args: &[String]) -> Result<Command> {
+    let mut day = None;
+    let mut kind = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            "--example" => kind = Some(InputKind::Example),
+            "--real" => kind = Some(InputKind::Real),
+            other => return Err(usage_error(format!("unexpected argument '{}'", other))),
+        }
+    }
+
+    Ok(Command::Check { day, kind })
+}
+
+fn parse_bench(args: &[String]) -> Result<Command> {
+    let mut options = BenchOptions::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(iter.next(), "--day")?),
+            "--example" => options.kind = InputKind::Example,
+            "--real" => options.kind = InputKind::Real,
+            "--runs" => options.runs = parse_number(iter.next(), "--runs")?,
+            "--threshold" => options.threshold = parse_number(iter.next(), "--threshold")?,
+            "--baseline" => options.baseline = Some(parse_value(iter.next(), "--baseline")?),
+            "--save-baseline" => {
+                options.save_baseline = Some(parse_value(iter.next(), "--save-baseline")?)
+            }
+            other => return Err(usage_error(format!("unexpected argument '{}'", other))),
+        }
+    }
+
+    if options.runs == 0 {
+        return Err(usage_error("--runs must be at least 1"));
+    }
+    Ok(Command::Bench(options))
+}
+
+fn parse_watch(args: &[String]) -> Result<Command> {
+    let mut day = None;
+    let mut kind = None;
+    let mut interval = 500;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            "--example" => kind = Some(InputKind::Example),
+            "--real" => kind = Some(InputKind::Real),
+            "--interval" => interval = parse_number(iter.next(), "--interval")?,
+            other => return Err(usage_error(format!("unexpected argument '{}'", other))),
+        }
+    }
+
+    if interval == 0 {
+        return Err(usage_error("--interval must be at least 1"));
+    }
+    Ok(Command::Watch {
+        day: day.ok_or_else(|| usage_error("missing --day"))?,
+        kind,
+        interval,
+    })
+}
+
+fn parse_new(args: &[String]) -> Result<Command> {
+    let mut day = None;
+    let mut name = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            "--name" => name = Some(parse_value(iter.next(), "--name")?),
+            other => return Err(usage_error(format!("unexpected argument '{}'", other))),
+        }
+    }
+
+    Ok(Command::New {
+        day: day.ok_or_else(|| usage_error("missing --day"))?,
+        name: name.ok_or_else(|| usage_error("missing --name"))?,
+    })
+}
+
+fn parse_extract(args: &[String]) -> Result<Command> {
+    let mut page = None;
+    let mut day = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            flag if flag.starts_with("--") => {
+                return Err(usage_error(format!("unknown option '{}'", flag)))
+            }
+            path => {
+                if page.replace(path.to_string()).is_some() {
+                    return Err(usage_error("give only one puzzle page"));
+                }
+            }
+        }
+    }
+
+    Ok(Command::Extract {
+        page: page.ok_or_else(|| usage_error("missing puzzle page"))?,
+        day,
+    })
+}
+
+fn parse_generate(args: &[String]) -> Result<Command> {
+    let mut day = None;
+    let mut size = 100;
+    let mut seed = DEFAULT_SEED;
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(iter.next(), "--day")?),
+            "--size" => size = parse_number(iter.next(), "--size")?,
+            "--seed" => seed = parse_number(iter.next(), "--seed")?,
+            flag if flag.starts_with("--") => {
+                return Err(usage_error(format!("unknown option '{}'", flag)))
+            }
+            path => {
+                if output.replace(path.to_string()).is_some() {
+                    return Err(usage_error("give only one output file"));
+                }
+            }
+        }
+    }
+
+    if size == 0 {
+        return Err(usage_error("--size must be at least 1"));
+    }
+    Ok(Command::Generate {
+        day: day.ok_or_else(|| usage_error("missing --day"))?,
+        size,
+        seed,
+        output,
+    })
+}
+
+/// The value that follows `flag`, or a usage error if there is none.
+pub fn parse_value(value: Option<&String>, flag: &str) -> Result<String> {
+    value
+        .cloned()
+        .ok_or_else(|| usage_error(format!("{} requires a value", flag)))
+}
+
+/// The number that follows `flag`, or a usage error if it is missing or not a number.
+pub fn parse_number<T: FromStr>(value: Option<&String>, flag: &str) -> Result<T> {
+    let value = parse_value(value, flag)?;
+    value
+        .parse()
+        .map_err(|_| usage_error(format!("{} expects a number, got '{}'", flag, value)))
+}
+
+fn usage_error(message: impl Into<String>) -> Error {
+    Error::Usage(message.into())
+}
+
+pub fn usage(program: &str) -> String {
+    format!(
+        "Usage: {0} [--format text|json] <command>\n  {0} run --day <N> --part <1|2> [--example | --real | <input file> | -]\n  {0} run --all [--example | --real] [--parallel]\n  {0} list\n  {0} inputs [--day <N>]\n  {0} check [--day <N>] [--example | --real]\n  {0} bench [--day <N>] [--example | --real] [--runs <N>] [--baseline <file>] [--save-baseline <file>] [--threshold <percent>]\n  {0} watch --day <N> [--example | --real] [--interval <ms>]\n  {0} new --day <N> --name <kebab-case-name>\n  {0} extract [--day <N>] <puzzle page.html | ->\n  {0} generate --day <N> [--size <N>] [--seed <N>] [<output file>]",
+        program
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        let parsed = Args::from_args(&args("run --day 7 --part 2 input.txt")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Run {
+                day: 7,
+                part: 2,
+                input: InputSource::File("input.txt".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_run_discovered_input() {
+        let parsed = Args::from_args(&args("run --day 7 --part 1 --example")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Run {
+                day: 7,
+                part: 1,
+                input: InputSource::Discover(InputKind::Example),
+            }
+        );
+
+        let parsed = Args::from_args(&args("run --day 7 --part 1")).unwrap();
+        assert!(matches!(
+            parsed.command,
+            Command::Run {
+                input: InputSource::Discover(InputKind::Real),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_run_all() {
+        let parsed = Args::from_args(&args("run --all --parallel")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::RunAll {
+                kind: InputKind::Real,
+                parallel: true,
+            }
+        );
+
+        let parsed = Args::from_args(&args("run --example --all")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::RunAll {
+                kind: InputKind::Example,
+                parallel: false,
+            }
+        );
+
+        assert!(Args::from_args(&args("run --all --day 7")).is_err());
+        assert!(Args::from_args(&args("run --all input.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_inputs() {
+        let parsed = Args::from_args(&args("inputs --day 2")).unwrap();
+        assert_eq!(parsed.command, Command::Inputs { day: Some(2) });
+
+        let parsed = Args::from_args(&args("inputs")).unwrap();
+        assert_eq!(parsed.command, Command::Inputs { day: None });
+        assert_eq!(parsed.format, Format::Text);
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let parsed =
+            Args::from_args(&args("run --day 7 --part 1 --format json --example")).unwrap();
+        assert_eq!(parsed.format, Format::Json);
+        assert!(matches!(parsed.command, Command::Run { day: 7, .. }));
+
+        let parsed = Args::from_args(&args("--format text list")).unwrap();
+        assert_eq!(parsed.format, Format::Text);
+        assert!(Args::from_args(&args("list --format yaml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let parsed = Args::from_args(&args("check --real")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Check {
+                day: None,
+                kind: Some(InputKind::Real),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_run_and_list() {
+        let parsed = Args::from_args(&args("run --day 1 --part 1 -")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Run {
+                day: 1,
+                part: 1,
+                input: InputSource::File("-".to_string()),
+            }
+        );
+
+        let parsed = Args::from_args(&args("list")).unwrap();
+        assert_eq!(parsed.command, Command::List);
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let parsed = Args::from_args(&args("bench --day 9 --runs 3 --baseline base.txt")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Bench(BenchOptions {
+                day: Some(9),
+                runs: 3,
+                baseline: Some("base.txt".to_string()),
+                ..BenchOptions::default()
+            })
+        );
+
+        assert!(Args::from_args(&args("bench --runs 0")).is_err());
+        assert!(Args::from_args(&args("bench --save-baseline")).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        let parsed = Args::from_args(&args("watch --day 6 --example")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Watch {
+                day: 6,
+                kind: Some(InputKind::Example),
+                interval: 500,
+            }
+        );
+
+        assert!(Args::from_args(&args("watch --interval 100")).is_err());
+        assert!(Args::from_args(&args("watch --day 6 --interval 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        let parsed = Args::from_args(&args("new --day 15 --name warehouse-woes")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::New {
+                day: 15,
+                name: "warehouse-woes".to_string(),
+            }
+        );
+
+        assert!(Args::from_args(&args("new --day 15")).is_err());
+        assert!(Args::from_args(&args("new --name warehouse-woes")).is_err());
+    }
+
+    #[test]
+    fn test_parse_extract() {
+        let parsed = Args::from_args(&args("extract day15.html")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Extract {
+                page: "day15.html".to_string(),
+                day: None,
+            }
+        );
+
+        assert!(Args::from_args(&args("extract --day 15")).is_err());
+        assert!(Args::from_args(&args("extract a.html b.html")).is_err());
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let parsed =
+            Args::from_args(&args("generate --day 9 --size 1000000 --seed 7 big.txt")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Generate {
+                day: 9,
+                size: 1_000_000,
+                seed: 7,
+                output: Some("big.txt".to_string()),
+            }
+        );
+
+        let parsed = Args::from_args(&args("generate --day 1")).unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::Generate {
+                day: 1,
+                size: 100,
+                seed: DEFAULT_SEED,
+                output: None,
+            }
+        );
+
+        assert!(Args::from_args(&args("generate --size 10")).is_err());
+        assert!(Args::from_args(&args("generate --day 1 --size 0")).is_err());
+        assert!(Args::from_args(&args("generate --day 1 a.txt b.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_arguments() {
+        assert!(Args::from_args(&args("")).is_err());
+        assert!(Args::from_args(&args("run --day 7 input.txt")).is_err());
+        assert!(Args::from_args(&args("run --day 7 --part 3 input.txt")).is_err());
+        assert!(Args::from_args(&args("run --day x --part 1 input.txt")).is_err());
+        assert!(Args::from_args(&args("run --day 7 --part 1 --example input.txt")).is_err());
+        assert!(Args::from_args(&args("inputs 7")).is_err());
+        assert!(matches!(
+            Args::from_args(&args("benchmark")),
+            Err(Error::Usage(_))
+        ));
+    }
+}
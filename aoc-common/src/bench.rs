@@ -0,0 +1,256 @@
+use crate::error::{parse_token, Error, Result};
+use crate::input::read_file;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the system allocator and counts every allocation.
+///
+/// Counts are only collected when the binary installs it with `#[global_allocator]`;
+/// otherwise [`allocation_count`] stays at zero.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Number of allocations made so far by the whole process.
+pub fn allocation_count() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// The piece of a day that is being timed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part(u8),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+impl std::str::FromStr for Stage {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, ()> {
+        match s {
+            "parse" => Ok(Stage::Parse),
+            _ => s
+                .strip_prefix("part")
+                .and_then(|part| part.parse().ok())
+                .map(Stage::Part)
+                .ok_or(()),
+        }
+    }
+}
+
+/// Timings of repeated runs of one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    /// Median number of allocations made by a single run.
+    pub allocations: u64,
+}
+
+/// Runs `f` `runs` times (at least once) and collects its timings.
+/// The first error returned by `f` stops the measurement.
+pub fn measure<T>(runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    let runs = runs.max(1);
+    let mut times = Vec::with_capacity(runs);
+    let mut allocations = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let allocations_before = allocation_count();
+        let start = Instant::now();
+        let output = f()?;
+        times.push(start.elapsed());
+        allocations.push(allocation_count() - allocations_before);
+        drop(output);
+    }
+
+    times.sort();
+    allocations.sort();
+    Ok(Stats {
+        min: times[0],
+        median: times[runs / 2],
+        max: times[runs - 1],
+        allocations: allocations[runs / 2],
+    })
+}
+
+/// Formats a duration with a unit that keeps three or four significant digits.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+/// The median time and allocation count recorded for one stage of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaselineEntry {
+    pub day: u8,
+    pub stage: Stage,
+    pub median: Duration,
+    pub allocations: u64,
+}
+
+/// Saved benchmark results, one per line as `<day> <stage> <median ns> <allocations>`.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            let [day, stage, median, allocations] = fields[..] else {
+                return Err(Error::malformed(
+                    index,
+                    line,
+                    trimmed,
+                    "expected '<day> <stage> <median ns> <allocations>'",
+                ));
+            };
+
+            entries.push(BaselineEntry {
+                day: parse_token(index, line, day, "day")?,
+                stage: parse_token(index, line, stage, "stage")?,
+                median: Duration::from_nanos(parse_token(index, line, median, "duration")?),
+                allocations: parse_token(index, line, allocations, "allocation count")?,
+            });
+        }
+
+        Ok(Baseline { entries })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&read_file(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut output = String::from("# day stage median_ns allocations\n");
+        for entry in &self.entries {
+            output.push_str(&format!(
+                "{} {} {} {}\n",
+                entry.day,
+                entry.stage,
+                entry.median.as_nanos(),
+                entry.allocations
+            ));
+        }
+        fs::write(path, output)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&BaselineEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.stage == stage)
+    }
+}
+
+/// Relative change of `current` over `baseline`, in percent.
+pub fn change_percent(baseline: Duration, current: Duration) -> f64 {
+    let baseline = baseline.as_secs_f64();
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    (current.as_secs_f64() - baseline) / baseline * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure_counts_runs_and_allocations() {
+        let mut calls = 0;
+        let stats = measure(5, || {
+            calls += 1;
+            Ok(vec![0u8; 64])
+        })
+        .unwrap();
+
+        assert_eq!(calls, 5);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+        // Other tests run concurrently, so this is a lower bound.
+        assert!(stats.allocations >= 1);
+    }
+
+    #[test]
+    fn test_measure_stops_on_error() {
+        let result = measure(3, || Err::<(), _>(Error::NotImplemented { part: 2 }));
+        assert!(matches!(result, Err(Error::NotImplemented { part: 2 })));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let baseline =
+            Baseline::parse("# day stage median_ns allocations\n7 part2 1500000 42\n").unwrap();
+        let entry = baseline.get(7, Stage::Part(2)).unwrap();
+
+        assert_eq!(entry.median, Duration::from_micros(1500));
+        assert_eq!(entry.allocations, 42);
+        assert!(baseline.get(7, Stage::Parse).is_none());
+
+        let err = Baseline::parse("7 part3x 1 1\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 3: invalid stage 'part3x'");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.5ms");
+        assert_eq!(format_duration(Duration::from_millis(2500)), "2.50s");
+        let change = change_percent(Duration::from_millis(10), Duration::from_millis(15));
+        assert!((change - 50.0).abs() < 1e-9);
+    }
+}
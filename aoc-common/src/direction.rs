@@ -0,0 +1,223 @@
+use std::fmt;
+
+/// Something that moves a grid position by a fixed row and column offset.
+pub trait Heading: Copy {
+    /// Row and column offset of one step; rows grow downwards.
+    fn delta(self) -> (isize, isize);
+}
+
+/// One of the four orthogonal directions, with north pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All four directions, clockwise from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Parses an arrow (`^>v<`), a compass letter (`NESW`) or a move (`UDLR`).
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Direction::North),
+            '>' | 'E' | 'R' => Some(Direction::East),
+            'v' | 'S' | 'D' => Some(Direction::South),
+            '<' | 'W' | 'L' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// The arrow pointing this way, as drawn in the puzzle maps.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+}
+
+impl Heading for Direction {
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+/// One of the eight compass directions, diagonals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Compass {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Compass {
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Compass; 8] = [
+        Compass::North,
+        Compass::NorthEast,
+        Compass::East,
+        Compass::SouthEast,
+        Compass::South,
+        Compass::SouthWest,
+        Compass::West,
+        Compass::NorthWest,
+    ];
+
+    /// The four diagonal directions, clockwise from north-east.
+    pub const DIAGONALS: [Compass; 4] = [
+        Compass::NorthEast,
+        Compass::SouthEast,
+        Compass::SouthWest,
+        Compass::NorthWest,
+    ];
+
+    fn index(self) -> usize {
+        Compass::ALL
+            .iter()
+            .position(|&direction| direction == self)
+            .unwrap_or_default()
+    }
+
+    fn rotate(self, eighths: usize) -> Self {
+        Compass::ALL[(self.index() + eighths) % 8]
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(7)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Compass::North,
+            Direction::East => Compass::East,
+            Direction::South => Compass::South,
+            Direction::West => Compass::West,
+        }
+    }
+}
+
+impl Heading for Compass {
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Compass::North => (-1, 0),
+            Compass::NorthEast => (-1, 1),
+            Compass::East => (0, 1),
+            Compass::SouthEast => (1, 1),
+            Compass::South => (1, 0),
+            Compass::SouthWest => (1, -1),
+            Compass::West => (0, -1),
+            Compass::NorthWest => (-1, -1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+        }
+        assert_eq!(Direction::West.turn_right(), Direction::North);
+
+        for direction in Compass::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+        }
+        assert_eq!(Compass::NorthWest.turn_right(), Compass::North);
+        assert_eq!(Compass::SouthEast.reverse(), Compass::NorthWest);
+    }
+
+    #[test]
+    fn test_parse_directions() {
+        let parsed: Vec<_> = "^>v<".chars().filter_map(Direction::from_char).collect();
+        assert_eq!(parsed, Direction::ALL);
+        assert_eq!(Direction::from_char('E'), Some(Direction::East));
+        assert_eq!(Direction::from_char('L'), Some(Direction::West));
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::South.to_string(), "v");
+    }
+
+    #[test]
+    fn test_vectors() {
+        for direction in Direction::ALL {
+            let (d_row, d_col) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-d_row, -d_col));
+            assert_eq!(Compass::from(direction).delta(), (d_row, d_col));
+        }
+        assert!(Compass::DIAGONALS.iter().all(|d| d.is_diagonal()));
+        assert_eq!(Compass::SouthWest.delta(), (1, -1));
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// Everything that can go wrong while reading, parsing or solving a puzzle input.
+///
+/// Line and column numbers are 1-based so they can be matched against an editor.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A line that does not have the expected shape, e.g. a number that does not parse.
+    MalformedLine {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A character that is not allowed at this position of the input.
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A part of the input that must be present but was not found.
+    MissingSection(&'static str),
+    /// The input parsed, but describes a puzzle the solver cannot work with.
+    InvalidInput(String),
+    /// The day exists but this part has not been solved yet.
+    NotImplemented {
+        part: u8,
+    },
+    /// The registry lists this day, but not this part among its solved ones.
+    MissingPart {
+        day: u8,
+        name: &'static str,
+        part: u8,
+    },
+    /// No input file matches what was asked for.
+    MissingInput(String),
+    /// No solution is registered for this day.
+    UnknownDay(u8),
+    /// The command line could not be understood.
+    Usage(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Builds a [`Error::MalformedLine`] pointing at `token`, which must be a slice of `line`.
+    /// `line_index` is the 0-based index as produced by `lines().enumerate()`.
+    pub fn malformed(
+        line_index: usize,
+        line: &str,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Error::MalformedLine {
+            line: line_index + 1,
+            column: column_of(line, token),
+            message: message.into(),
+        }
+    }
+
+    /// Builds a [`Error::MalformedLine`] for a byte offset into the whole input.
+    pub fn malformed_at(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Error::MalformedLine {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Builds a [`Error::InvalidCharacter`] from 0-based line and character indices.
+    pub fn invalid_character(line_index: usize, char_index: usize, found: char) -> Self {
+        Error::InvalidCharacter {
+            line: line_index + 1,
+            column: char_index + 1,
+            found,
+        }
+    }
+}
+
+/// Parses `token`, a slice of `line`, reporting its position if it is not a valid `T`.
+pub fn parse_token<T: FromStr>(
+    line_index: usize,
+    line: &str,
+    token: &str,
+    what: &str,
+) -> Result<T> {
+    token.parse().map_err(|_| {
+        Error::malformed(
+            line_index,
+            line,
+            token,
+            format!("invalid {} '{}'", what, token),
+        )
+    })
+}
+
+/// 1-based character column at which `token` starts inside `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|&offset| offset <= line.len())
+        .unwrap_or(0);
+    line[..offset].chars().count() + 1
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::MalformedLine {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::InvalidCharacter {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: invalid character {:?}",
+                line, column, found
+            ),
+            Error::MissingSection(section) => write!(f, "missing {}", section),
+            Error::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            Error::NotImplemented { part } => write!(f, "part {} is not implemented yet", part),
+            Error::MissingPart { day, name, part } => {
+                write!(f, "day {} ({}) has no part {} yet", day, name, part)
+            }
+            Error::MissingInput(what) => write!(f, "could not find {}", what),
+            Error::UnknownDay(day) => write!(f, "day {} is not available", day),
+            Error::Usage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_reports_column() {
+        let line = "3   x4";
+        let token = line.split_whitespace().nth(1).unwrap();
+        let err = parse_token::<i32>(6, line, token, "number").unwrap_err();
+
+        assert_eq!(err.to_string(), "line 7, column 5: invalid number 'x4'");
+    }
+
+    #[test]
+    fn test_malformed_at_offset() {
+        let input = "abc\ndef";
+        let err = Error::malformed_at(input, 5, "bad");
+
+        assert_eq!(err.to_string(), "line 2, column 2: bad");
+    }
+}
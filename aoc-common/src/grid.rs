@@ -0,0 +1,338 @@
+use crate::direction::{Compass, Direction, Heading};
+use crate::error::{Error, Result};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A cell position: `row` counts down from the top, `col` to the right from the left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Pos { row, col }
+    }
+
+    /// Moves by a signed offset, or returns `None` if that leaves the first quadrant.
+    /// Use [`Grid::offset`] to also stay inside a grid.
+    pub fn offset(self, d_row: isize, d_col: isize) -> Option<Pos> {
+        Some(Pos {
+            row: self.row.checked_add_signed(d_row)?,
+            col: self.col.checked_add_signed(d_col)?,
+        })
+    }
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// A rectangular grid stored row by row in a single vector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, which must all be as wide as the first one.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::InvalidInput(format!(
+                    "row {} has {} cells, expected {}",
+                    index + 1,
+                    row.len(),
+                    width
+                )));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses one cell per character, skipping blank lines. A leading byte order mark and
+    /// trailing whitespace on each line are ignored. `parse_cell` returns `None` for
+    /// characters that are not allowed, which is reported with their position.
+    pub fn parse_with(input: &str, mut parse_cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut rows = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(col, c)| {
+                    parse_cell(c).ok_or_else(|| Error::invalid_character(index, col, c))
+                })
+                .collect::<Result<Vec<T>>>()?;
+            rows.push(row);
+        }
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.row * self.width + pos.col])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.row * self.width + pos.col])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `pos` by a signed offset, or returns `None` if that leaves the grid.
+    pub fn offset(&self, pos: Pos, d_row: isize, d_col: isize) -> Option<Pos> {
+        pos.offset(d_row, d_col).filter(|&next| self.contains(next))
+    }
+
+    /// Moves `pos` one step towards `heading`, or returns `None` if that leaves the grid.
+    pub fn step(&self, pos: Pos, heading: impl Heading) -> Option<Pos> {
+        let (d_row, d_col) = heading.delta();
+        self.offset(pos, d_row, d_col)
+    }
+
+    /// The up to four orthogonal neighbors of `pos` that lie inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// The up to eight neighbors of `pos`, diagonals included, that lie inside the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Compass::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// Positions from `start` (included) in steps of `(d_row, d_col)` until the edge.
+    /// Yields nothing if `start` is outside the grid.
+    pub fn ray(&self, start: Pos, d_row: isize, d_col: isize) -> impl Iterator<Item = Pos> + '_ {
+        let first = Some(start).filter(|&pos| self.contains(pos));
+        std::iter::successors(first, move |&pos| self.offset(pos, d_row, d_col))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos::new(row, col)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position, row by row, whose cell satisfies `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Every position whose cell equals `value`.
+    pub fn positions_of<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width, which only happens for empty grids.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {} is outside the grid", col);
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    /// Cells on the down-right diagonal starting at `start`.
+    pub fn diagonal(&self, start: Pos) -> impl Iterator<Item = &T> {
+        self.ray(start, 1, 1).map(move |pos| &self[pos])
+    }
+
+    /// Cells on the down-left diagonal starting at `start`.
+    pub fn anti_diagonal(&self, start: Pos) -> impl Iterator<Item = &T> {
+        self.ray(start, 1, -1).map(move |pos| &self[pos])
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Parses a grid of arbitrary characters.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{} is outside the grid", pos);
+        &self.cells[pos.row * self.width + pos.col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{} is outside the grid", pos);
+        &mut self.cells[pos.row * self.width + pos.col]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let err = Grid::<char>::parse("abc\nde\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: row 2 has 2 cells, expected 3"
+        );
+
+        let err = Grid::parse_with("12\n3x\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: invalid character 'x'");
+    }
+
+    #[test]
+    fn test_parse_ignores_bom_and_trailing_whitespace() {
+        let grid = Grid::parse_with("\u{feff}01 \r\n23\t\n  \n", |c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.to_string(), "01\n23");
+
+        let err = Grid::parse_with("\u{feff}0x\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 2: invalid character 'x'");
+    }
+
+    #[test]
+    fn test_bounds_and_neighbors() {
+        let grid = sample();
+        let corner = Pos::new(0, 0);
+
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.offset(corner, -1, 0), None);
+        assert_eq!(
+            grid.neighbors4(corner).collect::<Vec<_>>(),
+            vec![Pos::new(0, 1), Pos::new(1, 0)]
+        );
+        assert_eq!(grid.neighbors8(Pos::new(0, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_lines_through_the_grid() {
+        let grid = sample();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.diagonal(Pos::new(0, 0)).collect::<String>(), "ae");
+        assert_eq!(grid.anti_diagonal(Pos::new(0, 2)).collect::<String>(), "ce");
+        assert_eq!(grid.ray(Pos::new(1, 2), 0, -1).count(), 3);
+        assert_eq!(grid.ray(Pos::new(5, 5), 0, -1).count(), 0);
+    }
+
+    #[test]
+    fn test_find_and_positions_of() {
+        let grid = Grid::parse("a.a\n.a.\n").unwrap();
+
+        assert_eq!(grid.find(|&c| c == '.'), Some(Pos::new(0, 1)));
+        assert_eq!(grid.positions_of(&'a').count(), 3);
+        assert_eq!(
+            grid.map(|&c| c == 'a').iter().filter(|(_, &a)| a).count(),
+            3
+        );
+    }
+}
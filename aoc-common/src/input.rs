@@ -0,0 +1,244 @@
+use crate::error::{Error, Result};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Where inputs live, relative to the crate root: one `<day>-<name>` directory per day.
+pub const INPUT_DIR: &str = "input";
+
+/// Which of a day's inputs to pick when resolving by convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// The worked examples from the puzzle text, `test-input-<part>*.txt`.
+    Example,
+    /// The personal puzzle input, `input-<part>.txt`.
+    Real,
+}
+
+impl InputKind {
+    /// Tells example inputs from real ones by their file name.
+    pub fn of_file_name(file_name: &str) -> Self {
+        if file_name.starts_with(InputKind::Example.prefix()) {
+            InputKind::Example
+        } else {
+            InputKind::Real
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            InputKind::Example => "test-input-",
+            InputKind::Real => "input-",
+        }
+    }
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputKind::Example => write!(f, "example"),
+            InputKind::Real => write!(f, "real"),
+        }
+    }
+}
+
+/// One `input/<day>-<name>/` directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDir {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl InputDir {
+    /// The input files in this directory, sorted by file name.
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Picks the input of the given kind for a part.
+    ///
+    /// `input-<part>.txt` or `test-input-<part>.txt` is preferred; days that share one
+    /// input between both parts fall back to the part 1 file. Examples may carry a suffix
+    /// describing them, as in `test-input-1-simple-rating-1.txt`.
+    pub fn resolve(&self, kind: InputKind, part: u8) -> Result<PathBuf> {
+        let files = self.files()?;
+        let file_name = |path: &PathBuf| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        for candidate in [part, 1] {
+            let exact = format!("{}{}.txt", kind.prefix(), candidate);
+            let suffixed = format!("{}{}-", kind.prefix(), candidate);
+
+            if let Some(path) = files.iter().find(|path| file_name(path) == exact) {
+                return Ok(path.clone());
+            }
+            if let Some(path) = files
+                .iter()
+                .find(|path| file_name(path).starts_with(&suffixed))
+            {
+                return Ok(path.clone());
+            }
+        }
+
+        Err(Error::MissingInput(format!(
+            "{} input for day {} part {} in '{}'",
+            kind,
+            self.day,
+            part,
+            self.path.display()
+        )))
+    }
+
+    /// Describes how the directory name differs from `<day>-<expected_name>`, if it does.
+    pub fn naming_warning(&self, expected_name: &str) -> Option<String> {
+        if self.name == expected_name {
+            return None;
+        }
+
+        let reason = if is_kebab_case(&self.name) {
+            "does not match the puzzle name"
+        } else {
+            "is not kebab-case"
+        };
+        Some(format!(
+            "'{}' {}, expected '{}-{}'",
+            self.path.display(),
+            reason,
+            self.day,
+            expected_name
+        ))
+    }
+}
+
+pub(crate) fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|word| {
+            !word.is_empty()
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
+
+/// Finds every `<day>-<name>` directory under `root`, ordered by day.
+/// Entries that do not start with a day number are ignored.
+pub fn discover_inputs<P: AsRef<Path>>(root: P) -> Result<Vec<InputDir>> {
+    let mut dirs = Vec::new();
+
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let Some(dir_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((day, name)) = dir_name.split_once('-') else {
+            continue;
+        };
+        let Ok(day) = day.parse() else {
+            continue;
+        };
+
+        dirs.push(InputDir {
+            day,
+            name: name.to_string(),
+            path,
+        });
+    }
+
+    dirs.sort_by(|a, b| a.day.cmp(&b.day).then_with(|| a.name.cmp(&b.name)));
+    Ok(dirs)
+}
+
+/// Finds the input directory of one day under `root`.
+pub fn find_input_dir<P: AsRef<Path>>(root: P, day: u8) -> Result<InputDir> {
+    let root = root.as_ref();
+    discover_inputs(root)?
+        .into_iter()
+        .find(|dir| dir.day == day)
+        .ok_or_else(|| {
+            Error::MissingInput(format!(
+                "input directory for day {} in '{}'",
+                day,
+                root.display()
+            ))
+        })
+}
+
+/// Reads a whole puzzle input from any buffered reader, such as a file or stdin.
+pub fn read_input<R: BufRead>(mut reader: R) -> Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Opens an input file for buffered reading; the error names the file that failed.
+pub fn open_file<P: AsRef<Path>>(path: P) -> Result<BufReader<File>> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("could not read '{}': {}", path.display(), e),
+        )
+    })?;
+    Ok(BufReader::new(file))
+}
+
+/// Reads a whole input file into memory.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    read_input(open_file(path)?)
+}
+
+/// Reads the input from stdin, or from a file unless `path` is `-`.
+pub fn read_path_or_stdin(path: &str) -> Result<String> {
+    if path == "-" {
+        read_input(io::stdin().lock())
+    } else {
+        read_file(path)
+    }
+}
+
+/// Opens a file for buffered reading, or stdin if the path is `-`, for inputs too large to
+/// read into memory at once.
+pub fn open_path_or_stdin(path: &str) -> Result<Box<dyn BufRead>> {
+    if path == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(open_file(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_read_input_from_memory() {
+        let input = read_input("3   4\n4   3\n".as_bytes()).unwrap();
+        assert_eq!(input, "3   4\n4   3\n");
+    }
+
+    #[test]
+    fn test_read_file_names_missing_file() {
+        let err = read_file("input/does-not-exist.txt").unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+        assert!(err.to_string().contains("input/does-not-exist.txt"));
+    }
+}
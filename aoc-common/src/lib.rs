@@ -0,0 +1,23 @@
+//! Tooling shared by every year of Advent of Code solutions.
+//!
+//! A year's crate implements [`solution::Solution`] once per day, lists the days in a
+//! [`solution::Day`] table and hands that table to [`runner::main`], which provides the
+//! command line. Grids, directions and graph searches cover what most puzzles need.
+
+pub mod answers;
+pub mod args;
+pub mod bench;
+pub mod direction;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod property;
+pub mod puzzle_page;
+pub mod report;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod watch;
@@ -0,0 +1,259 @@
+use crate::error::{parse_token, Error, Result};
+use std::str::FromStr;
+
+/// One line of an input together with its 0-based index, so that everything parsed from it
+/// can report errors at the right line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub index: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// A [`Error::MalformedLine`] pointing at `token`, which must be a slice of this line.
+    pub fn error(&self, token: &str, message: impl Into<String>) -> Error {
+        Error::malformed(self.index, self.text, token, message)
+    }
+
+    /// Parses `token`, a slice of this line, as a `T`; `what` names it in the error.
+    pub fn parse<T: FromStr>(&self, token: &str, what: &str) -> Result<T> {
+        parse_token(self.index, self.text, token, what)
+    }
+
+    /// The line after `prefix`, or an error saying that `prefix` was expected.
+    pub fn strip_prefix(&self, prefix: &str) -> Result<&'a str> {
+        self.text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(self.text, format!("expected '{}'", prefix)))
+    }
+
+    /// Splits `token`, a slice of this line, around the first `delimiter`.
+    pub fn split_once(&self, token: &'a str, delimiter: &str) -> Result<(&'a str, &'a str)> {
+        token
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(token, format!("expected '{}'", delimiter)))
+    }
+
+    /// Every integer on the line, see [`integer_tokens`].
+    pub fn integers<T: FromStr>(&self, what: &str) -> Result<Vec<T>> {
+        integer_tokens(self.text)
+            .map(|token| self.parse(token, what))
+            .collect()
+    }
+
+    /// Exactly `N` integers, e.g. `let [x, y] = line.integers_n("coordinate")?`.
+    pub fn integers_n<T: FromStr, const N: usize>(&self, what: &str) -> Result<[T; N]> {
+        let values = self.integers(what)?;
+        let found = values.len();
+        values.try_into().map_err(|_| {
+            self.error(
+                self.text,
+                format!("expected {} {}s, found {}", N, what, found),
+            )
+        })
+    }
+
+    /// Parses whitespace-separated `name=value` fields, allowing only the given names.
+    pub fn record(&self, names: &[&str]) -> Result<Record<'a>> {
+        let fields = self
+            .text
+            .split_whitespace()
+            .map(|field| {
+                let (name, value) = field
+                    .split_once('=')
+                    .ok_or_else(|| self.error(field, "expected 'name=value'"))?;
+                if !names.contains(&name) {
+                    return Err(self.error(field, format!("unexpected field '{}'", name)));
+                }
+                Ok((name, value))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Record {
+            line: *self,
+            fields,
+        })
+    }
+}
+
+/// The `name=value` fields of a line, as parsed by [`Line::record`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    line: Line<'a>,
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Record<'a> {
+    /// The raw value of the field `name`.
+    pub fn get(&self, name: &str) -> Result<&'a str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|&(_, value)| value)
+            .ok_or_else(|| {
+                self.line
+                    .error(self.line.text, format!("missing field '{}='", name))
+            })
+    }
+
+    /// The field `name` parsed as a single value.
+    pub fn value<T: FromStr>(&self, name: &str, what: &str) -> Result<T> {
+        self.line.parse(self.get(name)?, what)
+    }
+
+    /// The field `name` parsed as an `x,y` pair.
+    pub fn pair<T: FromStr>(&self, name: &str, what: &str) -> Result<(T, T)> {
+        let (x, y) = self.line.split_once(self.get(name)?, ",")?;
+        Ok((self.line.parse(x, what)?, self.line.parse(y, what)?))
+    }
+}
+
+/// Every line of `input`, blank ones included.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| Line { index, text })
+}
+
+/// Parses every non-blank line of `input` with `parse_line`.
+pub fn parse_lines<T>(
+    input: &str,
+    parse_line: impl FnMut(Line<'_>) -> Result<T>,
+) -> Result<Vec<T>> {
+    lines(input)
+        .filter(|line| !line.text.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// Groups the non-blank lines of `input` into sections separated by one or more blank lines.
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = vec![Vec::new()];
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            if sections.last().is_some_and(|section| !section.is_empty()) {
+                sections.push(Vec::new());
+            }
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    sections.retain(|section| !section.is_empty());
+    sections
+}
+
+/// The integer-looking tokens of `text`, in order: runs of ASCII letters and digits that
+/// contain a digit, with a directly preceding `-` kept as a sign unless it follows a letter
+/// or digit. Words without digits such as `Button` are skipped, while tokens that mix
+/// letters and digits such as `3x` are returned so that parsing them reports an error.
+pub fn integer_tokens(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let start = pos;
+            if !bytes[pos].is_ascii_alphanumeric() {
+                pos += 1;
+                continue;
+            }
+            while pos < bytes.len() && bytes[pos].is_ascii_alphanumeric() {
+                pos += 1;
+            }
+            if !bytes[start..pos].iter().any(u8::is_ascii_digit) {
+                continue;
+            }
+
+            let negative = start > 0
+                && bytes[start - 1] == b'-'
+                && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric());
+            let start = if negative { start - 1 } else { start };
+            return Some(&text[start..pos]);
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_tokens() {
+        let tokens: Vec<&str> = integer_tokens("Button A: X+94, Y=-34 p=-1,2 1-3").collect();
+        assert_eq!(tokens, vec!["94", "-34", "-1", "2", "1", "3"]);
+
+        let line = Line {
+            index: 0,
+            text: "Button A: X+94, Y+3x",
+        };
+        let err = line.integers::<i64>("coordinate").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 19: invalid coordinate '3x'"
+        );
+
+        let line = Line {
+            index: 2,
+            text: "1 2 3",
+        };
+        assert_eq!(line.integers_n::<u8, 3>("number").unwrap(), [1, 2, 3]);
+        let err = line.integers_n::<u8, 2>("number").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: expected 2 numbers, found 3"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let sections = sections("\n47|53\n97|13\n\n\n75,47\n");
+        let texts: Vec<Vec<&str>> = sections
+            .iter()
+            .map(|section| section.iter().map(|line| line.text).collect())
+            .collect();
+
+        assert_eq!(texts, vec![vec!["47|53", "97|13"], vec!["75,47"]]);
+        assert_eq!(sections[1][0].index, 5);
+    }
+
+    #[test]
+    fn test_record() {
+        let line = Line {
+            index: 0,
+            text: "p=0,4 v=3,-3",
+        };
+        let record = line.record(&["p", "v"]).unwrap();
+        assert_eq!(record.pair::<i64>("v", "velocity").unwrap(), (3, -3));
+        assert_eq!(record.get("p").unwrap(), "0,4");
+
+        let err = line.record(&["p"]).unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 7: unexpected field 'v'");
+
+        let line = Line {
+            index: 0,
+            text: "p=0,4",
+        };
+        let err = line
+            .record(&["p", "v"])
+            .unwrap()
+            .pair::<i64>("v", "velocity");
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 1, column 1: missing field 'v='"
+        );
+    }
+
+    #[test]
+    fn test_parse_lines_skips_blank_lines() {
+        let values = parse_lines("1\n\n2\n", |line| line.parse::<u32>(line.text, "number"));
+        assert_eq!(values.unwrap(), vec![1, 2]);
+
+        let err = parse_lines("1\nx\n", |line| line.parse::<u32>(line.text, "number"));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 2, column 1: invalid number 'x'"
+        );
+    }
+}
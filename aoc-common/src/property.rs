@@ -0,0 +1,248 @@
+use crate::rng::Rng;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// Seed used when `AOC_PROPERTY_SEED` is not set, so that runs are reproducible by default.
+pub const DEFAULT_SEED: u64 = 0x2024_1225;
+
+/// Smaller versions of `value`, closest to zero first.
+pub fn shrink_int(value: i64) -> Vec<i64> {
+    let mut candidates = Vec::new();
+    for candidate in [0, value / 2, value - value.signum()] {
+        if candidate != value && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Smaller versions of `items`: with halves, then single items removed, then with one item
+/// replaced by each of its own shrinks.
+pub fn shrink_vec<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    let half = items.len() / 2;
+    if half > 0 {
+        candidates.push(items[half..].to_vec());
+        candidates.push(items[..half].to_vec());
+    }
+    for index in 0..items.len() {
+        let mut smaller = items.to_vec();
+        smaller.remove(index);
+        candidates.push(smaller);
+    }
+    for (index, item) in items.iter().enumerate() {
+        for shrunk in shrink_item(item) {
+            let mut smaller = items.to_vec();
+            smaller[index] = shrunk;
+            candidates.push(smaller);
+        }
+    }
+    candidates
+}
+
+/// Outcome check for differential tests: passes if the optimized and reference results agree.
+pub fn agree<T: PartialEq + fmt::Debug>(optimized: T, reference: T) -> Result<(), String> {
+    if optimized == reference {
+        Ok(())
+    } else {
+        Err(format!(
+            "optimized solver returned {:?}, reference returned {:?}",
+            optimized, reference
+        ))
+    }
+}
+
+/// A failed property, after shrinking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub name: String,
+    pub seed: u64,
+    /// Index of the generated case that failed first.
+    pub case: usize,
+    /// The smallest failing input found, rendered as puzzle input.
+    pub input: String,
+    pub message: String,
+    /// Where `input` was saved, if that worked.
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "property '{}' failed on case {} (AOC_PROPERTY_SEED={}): {}",
+            self.name, self.case, self.seed, self.message
+        )?;
+        if let Some(path) = &self.path {
+            writeln!(f, "minimal input saved to {}", path.display())?;
+        }
+        write!(f, "minimal input:\n{}", self.input)
+    }
+}
+
+type Generate<T> = Box<dyn Fn(&mut Rng, usize) -> T>;
+type Shrink<T> = Box<dyn Fn(&T) -> Vec<T>>;
+
+/// A randomized check that generated inputs satisfy a predicate. Cases grow from size 1 to
+/// the maximum size; the first failure is shrunk to a minimal input, which is rendered as a
+/// puzzle input and saved under the temporary directory so it can be run directly.
+///
+/// `AOC_PROPERTY_SEED` and `AOC_PROPERTY_CASES` override the seed and number of cases.
+pub struct Property<T> {
+    name: String,
+    seed: u64,
+    cases: usize,
+    max_size: usize,
+    generate: Generate<T>,
+    shrink: Shrink<T>,
+    render: Box<dyn Fn(&T) -> String>,
+}
+
+impl<T: fmt::Debug> Property<T> {
+    /// A property over values made by `generate` from a generator and a size.
+    pub fn new(name: &str, generate: impl Fn(&mut Rng, usize) -> T + 'static) -> Self {
+        let from_env = |name| env::var(name).ok().and_then(|value| value.parse().ok());
+        Property {
+            name: name.to_string(),
+            seed: from_env("AOC_PROPERTY_SEED").unwrap_or(DEFAULT_SEED),
+            cases: from_env("AOC_PROPERTY_CASES").map_or(200, |cases| cases as usize),
+            max_size: 20,
+            generate: Box::new(generate),
+            shrink: Box::new(|_| Vec::new()),
+            render: Box::new(|value| format!("{:?}\n", value)),
+        }
+    }
+
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// How to make a failing value smaller; without it failures are reported as generated.
+    pub fn shrink(mut self, shrink: impl Fn(&T) -> Vec<T> + 'static) -> Self {
+        self.shrink = Box::new(shrink);
+        self
+    }
+
+    /// How to write a value as puzzle input; defaults to its debug representation.
+    pub fn render(mut self, render: impl Fn(&T) -> String + 'static) -> Self {
+        self.render = Box::new(render);
+        self
+    }
+
+    /// Runs every case and returns the first failure, shrunk.
+    pub fn check(&self, predicate: impl Fn(&T) -> Result<(), String>) -> Result<(), Failure> {
+        for case in 0..self.cases {
+            let size = 1 + case * self.max_size / self.cases.max(1);
+            let mut rng = Rng::new(self.seed.wrapping_add(case as u64));
+            let value = (self.generate)(&mut rng, size);
+
+            if let Err(message) = predicate(&value) {
+                let (value, message) = self.minimize(value, message, &predicate);
+                let input = (self.render)(&value);
+                let path = env::temp_dir()
+                    .join("aoc-property")
+                    .join(format!("{}-{}-{}.txt", self.name, self.seed, case));
+                let saved = path
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|()| fs::write(&path, &input));
+
+                return Err(Failure {
+                    name: self.name.clone(),
+                    seed: self.seed,
+                    case,
+                    input,
+                    message,
+                    path: saved.ok().map(|()| path),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Property::check`], but panics with the failure, for use in tests.
+    pub fn assert(&self, predicate: impl Fn(&T) -> Result<(), String>) {
+        if let Err(failure) = self.check(predicate) {
+            panic!("{}", failure);
+        }
+    }
+
+    /// Greedily replaces the failing value by its first shrink that still fails, until no
+    /// shrink fails any more.
+    fn minimize(
+        &self,
+        mut value: T,
+        mut message: String,
+        predicate: &impl Fn(&T) -> Result<(), String>,
+    ) -> (T, String) {
+        'shrinking: loop {
+            for candidate in (self.shrink)(&value) {
+                if let Err(candidate_message) = predicate(&candidate) {
+                    value = candidate;
+                    message = candidate_message;
+                    continue 'shrinking;
+                }
+            }
+            return (value, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink_helpers() {
+        assert_eq!(shrink_int(10), vec![0, 5, 9]);
+        assert_eq!(shrink_int(-1), vec![0]);
+        assert!(shrink_int(0).is_empty());
+
+        let candidates = shrink_vec(&[4, 1], |&item| shrink_int(item));
+        assert_eq!(candidates[0], vec![1]);
+        assert!(candidates.contains(&vec![2, 1]));
+    }
+
+    #[test]
+    fn test_failures_are_shrunk() {
+        let property = Property::new("sum-below-50", |rng, size| {
+            (0..size).map(|_| rng.range(0..=30)).collect::<Vec<i64>>()
+        })
+        .shrink(|items| shrink_vec(items, |&item| shrink_int(item)))
+        .render(|items| {
+            let items: Vec<String> = items.iter().map(i64::to_string).collect();
+            items.join(" ") + "\n"
+        });
+
+        let failure = property
+            .check(|items| {
+                let sum: i64 = items.iter().sum();
+                (sum < 50).then_some(()).ok_or(format!("sum is {}", sum))
+            })
+            .unwrap_err();
+
+        // Decrementing any item of a larger failing sum still fails, and zeros can be dropped.
+        let minimal: Vec<i64> = failure
+            .input
+            .split_whitespace()
+            .map(|item| item.parse().unwrap())
+            .collect();
+        assert_eq!(minimal.iter().sum::<i64>(), 50);
+        assert!(minimal.iter().all(|&item| item > 0));
+        assert_eq!(failure.message, "sum is 50");
+        if let Some(path) = &failure.path {
+            assert_eq!(fs::read_to_string(path).unwrap(), failure.input);
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(property.check(|_| Ok(())), Ok(()));
+    }
+}
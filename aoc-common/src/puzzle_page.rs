@@ -0,0 +1,212 @@
+use crate::error::{Error, Result};
+
+/// A puzzle page as saved from the Advent of Code website, reduced to what is needed to
+/// set up a day offline: its title, and for each part the example inputs and the answer
+/// the puzzle text gives for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzlePage {
+    pub day: Option<u8>,
+    /// The title from the `--- Day N: Title ---` heading.
+    pub title: Option<String>,
+    /// One entry per `<article class="day-desc">`, i.e. per part that has been unlocked.
+    pub parts: Vec<PagePart>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PagePart {
+    /// Contents of the `<pre><code>` blocks, in page order.
+    pub examples: Vec<String>,
+    /// The last highlighted `<code><em>` value, which is where the puzzle text states the
+    /// answer for the example.
+    pub answer: Option<String>,
+}
+
+/// An example to save for one part: the input and, if the page gives it, its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example<'a> {
+    pub part: u8,
+    pub input: &'a str,
+    pub answer: Option<&'a str>,
+}
+
+impl PuzzlePage {
+    pub fn parse(html: &str) -> Result<Self> {
+        let parts: Vec<PagePart> = elements(html, "<article", "</article>")
+            .map(|article| PagePart {
+                examples: elements(article, "<pre><code>", "</code></pre>")
+                    .map(text)
+                    .collect(),
+                answer: elements(article, "<code><em>", "</em></code>")
+                    .last()
+                    .map(text),
+            })
+            .collect();
+        if parts.is_empty() {
+            return Err(Error::MissingSection("puzzle description"));
+        }
+
+        let heading = elements(html, "<h2>", "</h2>")
+            .map(text)
+            .find_map(|heading| {
+                let heading = heading.trim_matches(|c: char| c == '-' || c.is_whitespace());
+                let (day, title) = heading.strip_prefix("Day ")?.split_once(':')?;
+                Some((day.trim().parse().ok()?, title.trim().to_string()))
+            });
+
+        Ok(PuzzlePage {
+            day: heading.as_ref().map(|(day, _)| *day),
+            title: heading.map(|(_, title)| title),
+            parts,
+        })
+    }
+
+    /// The title in kebab-case, as used for input directories, e.g. `warehouse-woes`.
+    pub fn name(&self) -> Option<String> {
+        let title = self.title.as_ref()?;
+        let name = title
+            .to_ascii_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// The example of every unlocked part. A part without an example of its own reuses the
+    /// first example of the part before it, as part two usually does.
+    pub fn examples(&self) -> Vec<Example<'_>> {
+        let mut examples = Vec::new();
+        let mut previous: Option<&str> = None;
+
+        for (part, page_part) in (1..).zip(&self.parts) {
+            let Some(input) = page_part.examples.first().map(String::as_str).or(previous) else {
+                continue;
+            };
+            examples.push(Example {
+                part,
+                input,
+                answer: page_part.answer.as_deref(),
+            });
+            previous = Some(input);
+        }
+        examples
+    }
+}
+
+/// The contents of every element between `open` and `close`. `open` may be the start of a
+/// tag with attributes, e.g. `<article`, in which case the rest of the tag is skipped.
+fn elements<'a>(html: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    let mut rest = html;
+    std::iter::from_fn(move || {
+        let start = rest.find(open)? + open.len();
+        let start = if open.ends_with('>') {
+            start
+        } else {
+            start + rest[start..].find('>')? + 1
+        };
+        let end = start + rest[start..].find(close)?;
+        let element = &rest[start..end];
+        rest = &rest[end + close.len()..];
+        Some(element)
+    })
+}
+
+/// The text of an HTML fragment: tags are dropped and character references decoded.
+fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(index) = rest.find(['<', '&']) {
+        text.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if rest.starts_with('<') {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            text.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix('#')
+                .and_then(|code| match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                })
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                text.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 7: Bridge Repair ---</h2><p>For example:</p>
+<pre><code>190: 10 19
+3267: 81 40 27
+</code></pre>
+<p>Only <code>2</code> equations can be made true; their sum is <code><em>3267</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>42</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now the total is <code><em>11387</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_parse_page() {
+        let page = PuzzlePage::parse(PAGE).unwrap();
+
+        assert_eq!(page.day, Some(7));
+        assert_eq!(page.name().as_deref(), Some("bridge-repair"));
+        assert_eq!(
+            page.examples(),
+            vec![
+                Example {
+                    part: 1,
+                    input: "190: 10 19\n3267: 81 40 27\n",
+                    answer: Some("3267"),
+                },
+                Example {
+                    part: 2,
+                    input: "190: 10 19\n3267: 81 40 27\n",
+                    answer: Some("11387"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_decodes_html() {
+        assert_eq!(
+            text("<em>x</em> &lt;&gt; &amp;amp; &#35; &#x41; & b"),
+            "x <> &amp; # A & b"
+        );
+
+        let err = PuzzlePage::parse("<html></html>").unwrap_err();
+        assert_eq!(err.to_string(), "missing puzzle description");
+    }
+}
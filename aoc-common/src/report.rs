@@ -0,0 +1,214 @@
+use crate::solution::Answer;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How the runner prints its results and diagnostics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Sentences and tables meant to be read by a person.
+    #[default]
+    Text,
+    /// One JSON object per line, meant to be read by scripts.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Escapes `value` for use inside a JSON string literal.
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A value written as a JSON number.
+pub trait JsonNumber {
+    fn to_json(&self) -> String;
+}
+
+macro_rules! json_integers {
+    ($($t:ty),*) => {
+        $(impl JsonNumber for $t {
+            fn to_json(&self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+json_integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// JSON has no NaN or infinity, so those are written as `null`.
+impl JsonNumber for f64 {
+    fn to_json(&self) -> String {
+        if self.is_finite() {
+            self.to_string()
+        } else {
+            "null".to_string()
+        }
+    }
+}
+
+impl<T: JsonNumber> JsonNumber for &T {
+    fn to_json(&self) -> String {
+        (*self).to_json()
+    }
+}
+
+/// Builds a single-line JSON object, keeping fields in insertion order.
+#[derive(Debug, Default)]
+pub struct JsonObject {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl JsonObject {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn string(mut self, key: &'static str, value: &str) -> Self {
+        self.fields.push((key, format!("\"{}\"", escape(value))));
+        self
+    }
+
+    pub fn number(mut self, key: &'static str, value: impl JsonNumber) -> Self {
+        self.fields.push((key, value.to_json()));
+        self
+    }
+
+    pub fn boolean(mut self, key: &'static str, value: bool) -> Self {
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    /// Numeric answers are written as JSON numbers, text answers as strings.
+    pub fn answer(self, key: &'static str, answer: &Answer) -> Self {
+        match answer {
+            Answer::Number(n) => self.number(key, n),
+            Answer::Text(s) => self.string(key, s),
+        }
+    }
+
+    /// A duration in seconds.
+    pub fn seconds(mut self, key: &'static str, duration: Duration) -> Self {
+        self.fields
+            .push((key, format!("{:.9}", duration.as_secs_f64())));
+        self
+    }
+
+    pub fn strings(mut self, key: &'static str, values: &[String]) -> Self {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| format!("\"{}\"", escape(value)))
+            .collect();
+        self.fields.push((key, format!("[{}]", values.join(","))));
+        self
+    }
+}
+
+impl fmt::Display for JsonObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "\"{}\":{}", key, value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A diagnostic line, `{"level": ..., "message": ...}`, in the given format.
+pub fn diagnostic(format: Format, level: &'static str, message: &str) -> String {
+    match format {
+        Format::Text => {
+            let mut label = level.to_string();
+            label[..1].make_ascii_uppercase();
+            format!("{}: {}", label, message)
+        }
+        Format::Json => JsonObject::new()
+            .string("level", level)
+            .string("message", message)
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_record() {
+        let record = JsonObject::new()
+            .number("day", 7)
+            .number("part", 2)
+            .string("input", "input/7-bridge-repair/input-2.txt")
+            .answer("answer", &Answer::Number(11387))
+            .seconds("elapsed", Duration::from_millis(1500));
+
+        assert_eq!(
+            record.to_string(),
+            r#"{"day":7,"part":2,"input":"input/7-bridge-repair/input-2.txt","answer":11387,"elapsed":1.500000000}"#
+        );
+    }
+
+    #[test]
+    fn test_numbers_and_booleans() {
+        assert_eq!(
+            JsonObject::new()
+                .number("change", 12.5)
+                .number("nan", f64::NAN)
+                .number("inf", f64::INFINITY)
+                .number("big", i128::MIN)
+                .boolean("regression", true)
+                .to_string(),
+            r#"{"change":12.5,"nan":null,"inf":null,"big":-170141183460469231731687303715884105728,"regression":true}"#
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a \"b\"\\\n\u{1}"), r#"a \"b\"\\\n\u0001"#);
+        assert_eq!(
+            JsonObject::new()
+                .answer("answer", &Answer::Text("x,y".to_string()))
+                .strings("files", &["a.txt".to_string(), "b.txt".to_string()])
+                .to_string(),
+            r#"{"answer":"x,y","files":["a.txt","b.txt"]}"#
+        );
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        assert_eq!(
+            diagnostic(Format::Text, "warning", "odd name"),
+            "Warning: odd name"
+        );
+        assert_eq!(
+            diagnostic(Format::Json, "error", "day 12 is not available"),
+            r#"{"level":"error","message":"day 12 is not available"}"#
+        );
+    }
+}
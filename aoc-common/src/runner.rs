@@ -0,0 +1,925 @@
+use crate::answers::{Answers, KnownAnswer, Verdict, ANSWERS_FILE};
+use crate::args::{usage, Args, BenchOptions, Command, InputSource};
+use crate::bench::{change_percent, format_duration, measure, Baseline, BaselineEntry, Stage};
+use crate::error::{Error, Result};
+use crate::input::{
+    discover_inputs, find_input_dir, read_file, read_path_or_stdin, InputDir, InputKind, INPUT_DIR,
+};
+use crate::puzzle_page::PuzzlePage;
+use crate::report::{diagnostic, Format, JsonObject};
+use crate::rng::Rng;
+use crate::scaffold::NewDay;
+use crate::solution::{find_day, Answer, Day};
+use crate::watch::Snapshot;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, process, thread};
+
+fn list_days(days: &[Day], format: Format) {
+    for day in days {
+        match format {
+            Format::Text => {
+                let parts: Vec<String> = (1..=day.parts).map(|part| part.to_string()).collect();
+                println!(
+                    "Day {:>2}: {:<26} parts {}",
+                    day.number,
+                    day.name,
+                    parts.join(", ")
+                )
+            }
+            Format::Json => println!(
+                "{}",
+                JsonObject::new()
+                    .number("day", day.number)
+                    .string("name", day.name)
+                    .number("parts", day.parts)
+            ),
+        }
+    }
+}
+
+/// Prints a warning when an input directory is not named after its registered day.
+fn warn_about_name(days: &[Day], format: Format, dir: &InputDir) {
+    if let Ok(entry) = find_day(days, dir.day) {
+        if let Some(warning) = dir.naming_warning(entry.name) {
+            eprintln!("{}", diagnostic(format, "warning", &warning));
+        }
+    }
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn list_inputs(days: &[Day], format: Format, day: Option<u8>) -> Result<()> {
+    let dirs = match day {
+        Some(day) => vec![find_input_dir(INPUT_DIR, day)?],
+        None => discover_inputs(INPUT_DIR)?,
+    };
+
+    for dir in &dirs {
+        warn_about_name(days, format, dir);
+        let files: Vec<String> = dir.files()?.iter().map(|file| file_name(file)).collect();
+
+        match format {
+            Format::Text => {
+                println!("Day {:>2}: {}", dir.day, dir.path.display());
+                for file in files {
+                    println!("        {}", file);
+                }
+            }
+            Format::Json => println!(
+                "{}",
+                JsonObject::new()
+                    .number("day", dir.day)
+                    .string("path", &dir.path.to_string_lossy())
+                    .strings("files", &files)
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn run(days: &[Day], format: Format, day: u8, part: u8, source: &InputSource) -> Result<()> {
+    let entry = find_day(days, day)?;
+    entry.check_part(part)?;
+    let (input_name, input) = match source {
+        InputSource::File(path) => (path.clone(), read_path_or_stdin(path)?),
+        InputSource::Discover(kind) => {
+            let dir = find_input_dir(INPUT_DIR, day)?;
+            warn_about_name(days, format, &dir);
+            let path = dir.resolve(*kind, part)?;
+            (path.to_string_lossy().into_owned(), read_file(path)?)
+        }
+    };
+
+    let start = Instant::now();
+    let puzzle = (entry.parse)(&input)?;
+    let answer = puzzle.part(part)?;
+    let elapsed = start.elapsed();
+
+    match format {
+        Format::Text => println!("Day {} part {}: {}", day, part, answer),
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("day", day)
+                .number("part", part)
+                .string("input", &input_name)
+                .answer("answer", &answer)
+                .seconds("elapsed", elapsed)
+        ),
+    }
+    Ok(())
+}
+
+/// One part of one day to run over one input file.
+struct Job<'a> {
+    entry: &'a Day,
+    part: u8,
+    path: PathBuf,
+}
+
+impl Job<'_> {
+    fn solve(&self) -> (Result<Answer>, Duration) {
+        let start = Instant::now();
+        let result = self
+            .entry
+            .parse_file(&self.path)
+            .and_then(|puzzle| puzzle.part(self.part));
+        (result, start.elapsed())
+    }
+}
+
+/// Runs both parts of every registered day over its example or real input, optionally on
+/// all cores, and prints a table of answers, timings and verdicts against the answers file.
+fn run_all(days: &[Day], format: Format, kind: InputKind, parallel: bool) -> Result<ExitCode> {
+    let answers = Answers::load(ANSWERS_FILE)?;
+    let mut jobs = Vec::new();
+    for entry in days {
+        let dir = match find_input_dir(INPUT_DIR, entry.number) {
+            Ok(dir) => dir,
+            Err(e) => {
+                let message = format!("day {} skipped, {}", entry.number, e);
+                eprintln!("{}", diagnostic(format, "warning", &message));
+                continue;
+            }
+        };
+        warn_about_name(days, format, &dir);
+
+        for part in 1..=2 {
+            if let Ok(path) = dir.resolve(kind, part) {
+                jobs.push(Job { entry, part, path });
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let results: Vec<_> = if parallel {
+        jobs.par_iter().map(Job::solve).collect()
+    } else {
+        jobs.iter().map(Job::solve).collect()
+    };
+    let wall_time = start.elapsed();
+
+    if format == Format::Text {
+        println!(
+            "{:>3}  {:>4}  {:>20}  {:>10}  Verdict",
+            "Day", "Part", "Answer", "Time"
+        );
+    }
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    let mut total_time = Duration::ZERO;
+    for (job, (result, elapsed)) in jobs.iter().zip(results) {
+        let file_name = file_name(&job.path);
+        let record = JsonObject::new()
+            .number("day", job.entry.number)
+            .number("part", job.part)
+            .string("input", &job.path.to_string_lossy())
+            .seconds("elapsed", elapsed);
+
+        let (answer, verdict, record) = match result {
+            Ok(answer) => {
+                let verdict = answers.check(job.entry.number, job.part, &file_name, &answer);
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    Verdict::Fail { .. } => failed += 1,
+                    Verdict::Unknown => unknown += 1,
+                }
+                let mut record = record
+                    .answer("answer", &answer)
+                    .string("verdict", verdict.label());
+                if let Verdict::Fail { expected } = &verdict {
+                    record = record.string("expected", expected);
+                }
+                (answer.to_string(), verdict.to_string(), record)
+            }
+            Err(Error::NotImplemented { .. }) => continue,
+            Err(e) => {
+                failed += 1;
+                let record = record
+                    .string("verdict", "error")
+                    .string("error", &e.to_string());
+                (String::new(), format!("ERROR {}", e), record)
+            }
+        };
+        total_time += elapsed;
+
+        match format {
+            Format::Text => println!(
+                "{:>3}  {:>4}  {:>20}  {:>10}  {}",
+                job.entry.number,
+                job.part,
+                answer,
+                format_duration(elapsed),
+                verdict
+            ),
+            Format::Json => println!("{}", record),
+        }
+    }
+
+    match format {
+        Format::Text => println!(
+            "{} passed, {} failed, {} unknown in {} ({} summed over parts)",
+            passed,
+            failed,
+            unknown,
+            format_duration(wall_time),
+            format_duration(total_time)
+        ),
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("passed", passed)
+                .number("failed", failed)
+                .number("unknown", unknown)
+                .seconds("elapsed", wall_time)
+                .seconds("summed", total_time)
+        ),
+    }
+    Ok(if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// The inputs of one part: the conventional input of each kind, followed by any other input
+/// of those kinds that has a recorded answer for the part.
+fn part_inputs(dir: &InputDir, part: u8, kinds: &[InputKind], answers: &Answers) -> Vec<PathBuf> {
+    let mut inputs: Vec<PathBuf> = kinds
+        .iter()
+        .filter_map(|&kind| dir.resolve(kind, part).ok())
+        .collect();
+    for known in answers.for_part(dir.day, part) {
+        let path = dir.path.join(&known.input);
+        if kinds.contains(&InputKind::of_file_name(&known.input)) && !inputs.contains(&path) {
+            inputs.push(path);
+        }
+    }
+    inputs
+}
+
+/// Runs every implemented part on its example and real inputs and compares the answers
+/// with the answers file. Fails if any answer is wrong or any solver returns an error.
+fn check(
+    days: &[Day],
+    format: Format,
+    day: Option<u8>,
+    kind: Option<InputKind>,
+) -> Result<ExitCode> {
+    let answers = Answers::load(ANSWERS_FILE)?;
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => vec![InputKind::Example, InputKind::Real],
+    };
+
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    for entry in days
+        .iter()
+        .filter(|entry| day.is_none_or(|day| day == entry.number))
+    {
+        let dir = match find_input_dir(INPUT_DIR, entry.number) {
+            Ok(dir) => dir,
+            Err(e) => {
+                let message = format!("day {} skipped, {}", entry.number, e);
+                eprintln!("{}", diagnostic(format, "warning", &message));
+                continue;
+            }
+        };
+        warn_about_name(days, format, &dir);
+
+        for part in 1..=2 {
+            for path in part_inputs(&dir, part, &kinds, &answers) {
+                let file_name = file_name(&path);
+                let label = format!("Day {:>2} part {} {:<36}", entry.number, part, file_name);
+                let record = JsonObject::new()
+                    .number("day", entry.number)
+                    .number("part", part)
+                    .string("input", &path.to_string_lossy());
+
+                let start = Instant::now();
+                let result = entry.parse_file(&path).and_then(|puzzle| puzzle.part(part));
+                let elapsed = start.elapsed();
+
+                match result {
+                    Ok(answer) => {
+                        let verdict = answers.check(entry.number, part, &file_name, &answer);
+                        match verdict {
+                            Verdict::Pass => passed += 1,
+                            Verdict::Fail { .. } => failed += 1,
+                            Verdict::Unknown => unknown += 1,
+                        }
+
+                        match format {
+                            Format::Text => {
+                                println!("{} {:<8} {}", label, verdict.to_string(), answer)
+                            }
+                            Format::Json => {
+                                let mut record = record
+                                    .answer("answer", &answer)
+                                    .seconds("elapsed", elapsed)
+                                    .string("verdict", verdict.label());
+                                if let Verdict::Fail { expected } = &verdict {
+                                    record = record.string("expected", expected);
+                                }
+                                println!("{}", record);
+                            }
+                        }
+                    }
+                    Err(Error::NotImplemented { .. }) => {}
+                    Err(e) => {
+                        failed += 1;
+                        match format {
+                            Format::Text => println!("{} ERROR    {}", label, e),
+                            Format::Json => println!(
+                                "{}",
+                                record
+                                    .seconds("elapsed", elapsed)
+                                    .string("verdict", "error")
+                                    .string("error", &e.to_string())
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    match format {
+        Format::Text => println!("{} passed, {} failed, {} unknown", passed, failed, unknown),
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("passed", passed)
+                .number("failed", failed)
+                .number("unknown", unknown)
+        ),
+    }
+    Ok(if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Times the parse and both parts of every selected day and prints min/median/max per stage.
+/// With a baseline, stages whose median got slower than the threshold are flagged and make
+/// the command fail.
+fn bench(days: &[Day], format: Format, options: &BenchOptions) -> Result<ExitCode> {
+    let baseline = options.baseline.as_ref().map(Baseline::load).transpose()?;
+    let mut results = Baseline::default();
+    let mut regressions = 0;
+
+    if format == Format::Text {
+        println!(
+            "{:>3}  {:<6} {:>10} {:>10} {:>10} {:>10}",
+            "Day", "Stage", "min", "median", "max", "allocs"
+        );
+    }
+    for entry in days
+        .iter()
+        .filter(|entry| options.day.is_none_or(|day| day == entry.number))
+    {
+        let dir = match find_input_dir(INPUT_DIR, entry.number) {
+            Ok(dir) => dir,
+            Err(e) => {
+                let message = format!("day {} skipped, {}", entry.number, e);
+                eprintln!("{}", diagnostic(format, "warning", &message));
+                continue;
+            }
+        };
+        warn_about_name(days, format, &dir);
+
+        for stage in [Stage::Parse, Stage::Part(1), Stage::Part(2)] {
+            let part = match stage {
+                Stage::Parse => 1,
+                Stage::Part(part) => part,
+            };
+            let Ok(path) = dir.resolve(options.kind, part) else {
+                if stage == Stage::Parse {
+                    let message =
+                        format!("day {} skipped, no {} input", entry.number, options.kind);
+                    eprintln!("{}", diagnostic(format, "warning", &message));
+                    break;
+                }
+                continue;
+            };
+            let input = read_file(path)?;
+
+            let stats = match stage {
+                Stage::Parse => measure(options.runs, || (entry.parse)(&input)),
+                Stage::Part(part) => {
+                    let puzzle = (entry.parse)(&input)?;
+                    measure(options.runs, || puzzle.part(part))
+                }
+            };
+            let stats = match stats {
+                Ok(stats) => stats,
+                Err(Error::NotImplemented { .. }) => continue,
+                Err(e) => return Err(e),
+            };
+
+            let mut row = format!(
+                "{:>3}  {:<6} {:>10} {:>10} {:>10} {:>10}",
+                entry.number,
+                stage.to_string(),
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.max),
+                stats.allocations
+            );
+            let mut record = JsonObject::new()
+                .number("day", entry.number)
+                .string("stage", &stage.to_string())
+                .seconds("min", stats.min)
+                .seconds("median", stats.median)
+                .seconds("max", stats.max)
+                .number("allocations", stats.allocations);
+            if let Some(previous) = baseline
+                .as_ref()
+                .and_then(|baseline| baseline.get(entry.number, stage))
+            {
+                let change = change_percent(previous.median, stats.median);
+                let regression = change > f64::from(options.threshold);
+                row.push_str(&format!("  {:+.1}%", change));
+                if stats.allocations != previous.allocations {
+                    row.push_str(&format!(
+                        " (allocs {} -> {})",
+                        previous.allocations, stats.allocations
+                    ));
+                }
+                if regression {
+                    regressions += 1;
+                    row.push_str("  REGRESSION");
+                }
+                record = record
+                    .number("change_percent", (change * 10.0).round() / 10.0)
+                    .number("baseline_allocations", previous.allocations)
+                    .boolean("regression", regression);
+            }
+            match format {
+                Format::Text => println!("{}", row),
+                Format::Json => println!("{}", record),
+            }
+
+            results.entries.push(BaselineEntry {
+                day: entry.number,
+                stage,
+                median: stats.median,
+                allocations: stats.allocations,
+            });
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        results.save(path)?;
+        let message = format!("saved baseline to {}", path);
+        eprintln!("{}", diagnostic(format, "info", &message));
+    }
+    if regressions > 0 {
+        let message = format!(
+            "{} stage(s) are more than {}% slower than the baseline",
+            regressions, options.threshold
+        );
+        eprintln!("{}", diagnostic(format, "error", &message));
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reruns one day whenever its input files, the answers file or its source file change,
+/// and prints every answer with its timing and how it differs from the previous run.
+/// Input and answer changes are picked up in place; a source change rebuilds the binary
+/// and restarts it, so the watch keeps going with the new code.
+fn watch(
+    program: &str,
+    days: &[Day],
+    format: Format,
+    day: u8,
+    kind: Option<InputKind>,
+    interval: u64,
+) -> Result<ExitCode> {
+    let entry = find_day(days, day)?;
+    // The running binary is replaced on rebuild, so remember where it was started from.
+    let exe = env::current_exe()?;
+    let source = Path::new("src").join(format!("{}.rs", entry.name.replace('-', "_")));
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => vec![InputKind::Example, InputKind::Real],
+    };
+
+    let mut snapshot = Snapshot::default();
+    let mut previous = env::var(PREVIOUS_ANSWERS_VAR)
+        .map(|answers| decode_previous(&answers))
+        .unwrap_or_default();
+    // The last error finding the inputs, printed once until the inputs are back.
+    let mut input_error = None;
+    loop {
+        let found = find_input_dir(INPUT_DIR, day).and_then(|dir| {
+            let files = dir.files()?;
+            Ok((dir, files))
+        });
+        let (dir, mut files) = match found {
+            Ok(found) => found,
+            Err(e) => {
+                let message = e.to_string();
+                if input_error.as_ref() != Some(&message) {
+                    eprintln!("{}", diagnostic(format, "error", &message));
+                    input_error = Some(message);
+                }
+                thread::sleep(Duration::from_millis(interval));
+                continue;
+            }
+        };
+        input_error = None;
+        files.push(PathBuf::from(ANSWERS_FILE));
+        files.push(source.clone());
+
+        let current = Snapshot::take(files);
+        let changed: Vec<String> = current
+            .changed_since(&snapshot)
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        let first = snapshot == Snapshot::default();
+        snapshot = current;
+
+        if !changed.is_empty() {
+            let message = if first {
+                format!("watching day {} in {}", day, dir.path.display())
+            } else {
+                format!("changed: {}", changed.join(", "))
+            };
+            eprintln!("{}", diagnostic(format, "info", &message));
+
+            if !first && changed.contains(&source.to_string_lossy().into_owned()) {
+                restart_rebuilt(program, format, &exe, &previous)?;
+            }
+            rerun(format, entry, &dir, &kinds, &mut previous);
+        }
+        thread::sleep(Duration::from_millis(interval));
+    }
+}
+
+/// Runs both parts of `entry` over its inputs once for [`watch`]. Errors are printed rather
+/// than returned so that the watch survives a half-edited input or answers file.
+fn rerun(
+    format: Format,
+    entry: &Day,
+    dir: &InputDir,
+    kinds: &[InputKind],
+    previous: &mut HashMap<(PathBuf, u8), String>,
+) {
+    let answers = match Answers::load(ANSWERS_FILE) {
+        Ok(answers) => answers,
+        Err(e) => {
+            let message = format!("{}: {}", ANSWERS_FILE, e);
+            eprintln!("{}", diagnostic(format, "error", &message));
+            Answers::default()
+        }
+    };
+
+    for part in 1..=2 {
+        for path in part_inputs(dir, part, kinds, &answers) {
+            let file_name = file_name(&path);
+            let start = Instant::now();
+            let puzzle = entry.parse_file(&path);
+            let parse_time = start.elapsed();
+            let start = Instant::now();
+            let result = puzzle.and_then(|puzzle| puzzle.part(part));
+            let part_time = start.elapsed();
+
+            let mut record = JsonObject::new()
+                .number("day", entry.number)
+                .number("part", part)
+                .string("input", &path.to_string_lossy())
+                .seconds("parse", parse_time)
+                .seconds("elapsed", part_time);
+            let label = format!("part {} {:<36}", part, file_name);
+            let timing = format!(
+                "parse {}, part {}",
+                format_duration(parse_time),
+                format_duration(part_time)
+            );
+
+            let answer = match result {
+                Ok(answer) => answer,
+                Err(Error::NotImplemented { .. }) => continue,
+                Err(e) => {
+                    match format {
+                        Format::Text => println!("{} ERROR    {}", label, e),
+                        Format::Json => println!(
+                            "{}",
+                            record
+                                .string("verdict", "error")
+                                .string("error", &e.to_string())
+                        ),
+                    }
+                    continue;
+                }
+            };
+
+            let verdict = answers.check(entry.number, part, &file_name, &answer);
+            let answer_text = answer.to_string();
+            let change = match previous.insert((path.clone(), part), answer_text.clone()) {
+                None => String::new(),
+                Some(before) if before == answer_text => " (unchanged)".to_string(),
+                Some(before) => {
+                    record = record.string("previous", &before);
+                    format!(" (was {})", before)
+                }
+            };
+            match format {
+                Format::Text => println!(
+                    "{} {:<8} {}{}  [{}]",
+                    label,
+                    verdict.to_string(),
+                    answer,
+                    change,
+                    timing
+                ),
+                Format::Json => println!(
+                    "{}",
+                    record
+                        .answer("answer", &answer)
+                        .string("verdict", verdict.label())
+                ),
+            }
+        }
+    }
+}
+
+/// Environment variable through which [`watch`] hands its last answers to the restarted
+/// process, so that the first run after a rebuild still shows what changed.
+const PREVIOUS_ANSWERS_VAR: &str = "AOC2024_WATCH_PREVIOUS";
+
+/// One `<part>\t<input path>\t<answer>` line per answer.
+fn encode_previous(previous: &HashMap<(PathBuf, u8), String>) -> String {
+    previous
+        .iter()
+        .map(|((path, part), answer)| format!("{}\t{}\t{}\n", part, path.display(), answer))
+        .collect()
+}
+
+fn decode_previous(encoded: &str) -> HashMap<(PathBuf, u8), String> {
+    encoded
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let part = fields.next()?.parse().ok()?;
+            let path = PathBuf::from(fields.next()?);
+            Some(((path, part), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Rebuilds the binary with the profile it was built with and replaces this process with
+/// the rebuilt `exe`, passing on the same arguments and the answers seen so far. Returns if
+/// the build fails, so the watch carries on with the code it has.
+fn restart_rebuilt(
+    program: &str,
+    format: Format,
+    exe: &Path,
+    previous: &HashMap<(PathBuf, u8), String>,
+) -> Result<()> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut build = process::Command::new(cargo);
+    build.args(["build", "--bin", program]);
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    if !build.status()?.success() {
+        let message = "build failed, still running the previous version";
+        eprintln!("{}", diagnostic(format, "warning", message));
+        return Ok(());
+    }
+
+    let mut restart = process::Command::new(exe);
+    restart
+        .args(env::args_os().skip(1))
+        .env(PREVIOUS_ANSWERS_VAR, encode_previous(previous));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(restart.exec().into())
+    }
+    #[cfg(not(unix))]
+    {
+        let status = restart.status()?;
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+/// Generates a new day in the current crate and lists the files it wrote.
+fn new_day(format: Format, day: u8, name: &str) -> Result<()> {
+    let new_day = NewDay::new(day, name)?;
+    let written: Vec<String> = new_day
+        .create(".")?
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
+    match format {
+        Format::Text => {
+            for path in &written {
+                println!("wrote {}", path);
+            }
+            println!(
+                "Paste the examples into {}/{}-{}/ and fill in their answers in src/{}.rs",
+                INPUT_DIR,
+                day,
+                name,
+                new_day.module_name()
+            );
+        }
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("day", day)
+                .string("name", name)
+                .strings("files", &written)
+        ),
+    }
+    Ok(())
+}
+
+/// Saves the examples of a saved puzzle page as the day's example inputs and records the
+/// answers the page gives for them. Example files and answers that exist are kept.
+fn extract(days: &[Day], format: Format, page_path: &str, day: Option<u8>) -> Result<()> {
+    let page = PuzzlePage::parse(&read_path_or_stdin(page_path)?)?;
+    let day = match (day, page.day) {
+        (Some(day), Some(page_day)) if day != page_day => {
+            return Err(Error::InvalidInput(format!(
+                "'{}' is the page of day {}, not day {}",
+                page_path, page_day, day
+            )))
+        }
+        (Some(day), _) | (None, Some(day)) => day,
+        (None, None) => return Err(Error::MissingSection("day number, pass --day")),
+    };
+
+    let dir = match find_input_dir(INPUT_DIR, day) {
+        Ok(dir) => dir,
+        Err(Error::MissingInput(_)) => {
+            let name = match find_day(days, day) {
+                Ok(entry) => entry.name.to_string(),
+                Err(_) => page.name().ok_or(Error::MissingSection("puzzle title"))?,
+            };
+            let path = Path::new(INPUT_DIR).join(format!("{}-{}", day, name));
+            fs::create_dir_all(&path)?;
+            InputDir { day, name, path }
+        }
+        Err(e) => return Err(e),
+    };
+    warn_about_name(days, format, &dir);
+
+    let answers = Answers::load(ANSWERS_FILE)?;
+    let mut new_answers = Vec::new();
+    for example in page.examples() {
+        let file_name = format!("test-input-{}.txt", example.part);
+        let path = dir.path.join(&file_name);
+        let written = fs::metadata(&path).map_or(true, |metadata| metadata.len() == 0);
+        if written {
+            fs::write(&path, example.input)?;
+        }
+
+        let mut record = JsonObject::new()
+            .number("day", day)
+            .number("part", example.part)
+            .string("input", &path.to_string_lossy())
+            .boolean("written", written);
+        let mut line = format!(
+            "Day {:>2} part {} {:<36} {}",
+            day,
+            example.part,
+            file_name,
+            if written { "written" } else { "kept" }
+        );
+        match example.answer {
+            Some(answer) => {
+                record = record.string("answer", answer);
+                line.push_str(&format!("  answer {}", answer));
+                if answers.get(day, example.part, &file_name).is_none() {
+                    new_answers.push(KnownAnswer {
+                        day,
+                        part: example.part,
+                        input: file_name,
+                        answer: answer.to_string(),
+                    });
+                }
+            }
+            None => {
+                let message = format!(
+                    "no highlighted answer for day {} part {}",
+                    day, example.part
+                );
+                eprintln!("{}", diagnostic(format, "warning", &message));
+            }
+        }
+        match format {
+            Format::Text => println!("{}", line),
+            Format::Json => println!("{}", record),
+        }
+    }
+
+    Answers::append(ANSWERS_FILE, &new_answers)
+}
+
+/// Writes a random input for `day` to `output`, or to stdout when there is none.
+fn generate(
+    days: &[Day],
+    format: Format,
+    day: u8,
+    size: usize,
+    seed: u64,
+    output: Option<&str>,
+) -> Result<()> {
+    let entry = find_day(days, day)?;
+    let input = (entry.generate)(&mut Rng::new(seed), size).ok_or_else(|| {
+        Error::InvalidInput(format!("day {} has no input generator", entry.number))
+    })?;
+
+    let Some(path) = output else {
+        io::stdout().lock().write_all(input.as_bytes())?;
+        return Ok(());
+    };
+    fs::write(path, &input)?;
+    match format {
+        Format::Text => println!(
+            "Day {:>2} size {} seed {}: wrote {} bytes to {}",
+            day,
+            size,
+            seed,
+            input.len(),
+            path
+        ),
+        Format::Json => println!(
+            "{}",
+            JsonObject::new()
+                .number("day", day)
+                .number("size", size)
+                .number("seed", seed)
+                .string("output", path)
+                .number("bytes", input.len())
+        ),
+    }
+    Ok(())
+}
+
+/// Parses the command line and runs the command on `days`. `program` is the name of the
+/// binary, shown in the usage and rebuilt by `watch`.
+pub fn main(program: &str, days: &[Day]) -> ExitCode {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", usage(program));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let format = args.format;
+    let result = match args.command {
+        Command::RunAll { kind, parallel } => run_all(days, format, kind, parallel),
+        Command::List => {
+            list_days(days, format);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Inputs { day } => list_inputs(days, format, day).map(|()| ExitCode::SUCCESS),
+        Command::Run { day, part, input } => {
+            run(days, format, day, part, &input).map(|()| ExitCode::SUCCESS)
+        }
+        Command::Check { day, kind } => check(days, format, day, kind),
+        Command::Bench(options) => bench(days, format, &options),
+        Command::Watch {
+            day,
+            kind,
+            interval,
+        } => watch(program, days, format, day, kind, interval),
+        Command::Extract { page, day } => {
+            extract(days, format, &page, day).map(|()| ExitCode::SUCCESS)
+        }
+        Command::New { day, name } => new_day(format, day, &name).map(|()| ExitCode::SUCCESS),
+        Command::Generate {
+            day,
+            size,
+            seed,
+            output,
+        } => generate(days, format, day, size, seed, output.as_deref()).map(|()| ExitCode::SUCCESS),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", diagnostic(format, "error", &e.to_string()));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::error::{Error, Result};
+use crate::input::{is_kebab_case, INPUT_DIR};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Source of a freshly generated day. `{type}`, `{day}` and `{name}` are
+/// filled in by [`NewDay::module_source`].
+const MODULE_TEMPLATE: &str = r#"use aoc_common::error::{Error, Result};
+use aoc_common::solution::{Answer, Solution};
+
+pub struct {type};
+
+impl Solution for {type} {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {
+        Err(Error::NotImplemented { part: 1 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Paste the examples from the puzzle text into the input files and replace the
+    // expected answers below with the ones given there.
+
+    #[test]
+    fn test_part1() {
+        let input = {type}::parse_file("input/{day}-{name}/test-input-1.txt").unwrap();
+        assert_eq!({type}::part1(&input).unwrap(), Answer::Number(0));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = {type}::parse_file("input/{day}-{name}/test-input-2.txt").unwrap();
+        assert_eq!({type}::part2(&input).unwrap(), Answer::Number(0));
+    }
+}
+"#;
+
+/// Example files created next to a new day, left empty until the puzzle text is pasted in.
+const EXAMPLE_FILES: [&str; 2] = ["test-input-1.txt", "test-input-2.txt"];
+
+/// A day to generate: its number and its puzzle name in kebab-case, e.g. `warehouse-woes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewDay {
+    pub day: u8,
+    pub name: String,
+}
+
+impl NewDay {
+    pub fn new(day: u8, name: &str) -> Result<Self> {
+        if !(1..=25).contains(&day) {
+            return Err(Error::InvalidInput(format!(
+                "day must be between 1 and 25, got {}",
+                day
+            )));
+        }
+        if !is_kebab_case(name) || !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+            return Err(Error::InvalidInput(format!(
+                "'{}' is not a kebab-case name starting with a letter",
+                name
+            )));
+        }
+
+        Ok(NewDay {
+            day,
+            name: name.to_string(),
+        })
+    }
+
+    /// `warehouse-woes` becomes `warehouse_woes`.
+    pub fn module_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    /// `warehouse-woes` becomes `WarehouseWoes`.
+    pub fn type_name(&self) -> String {
+        self.name
+            .split('-')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_uppercase().to_string() + chars.as_str()
+                })
+            })
+            .collect()
+    }
+
+    pub fn module_source(&self) -> String {
+        MODULE_TEMPLATE
+            .replace("{type}", &self.type_name())
+            .replace("{day}", &self.day.to_string())
+            .replace("{name}", &self.name)
+    }
+
+    /// Adds the `pub mod` line and the `DAYS` entry for this day to the source of a year's
+    /// `lib.rs`, keeping modules in alphabetical and days in numerical order.
+    pub fn register(&self, lib_source: &str) -> Result<String> {
+        let module = self.module_name();
+        let mod_line = format!("pub mod {};", module);
+        let day_line = format!(
+            "    Day::new::<{}::{}>({}, \"{}\"),",
+            module,
+            self.type_name(),
+            self.day,
+            self.name
+        );
+        let mut lines: Vec<&str> = lib_source.lines().collect();
+
+        let modules: Vec<(usize, &str)> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| Some((index, line.strip_prefix("pub mod ")?)))
+            .collect();
+        if modules
+            .iter()
+            .any(|&(_, name)| name == format!("{};", module))
+        {
+            return Err(Error::InvalidInput(format!(
+                "module '{}' is already declared",
+                module
+            )));
+        }
+        let mod_index = modules
+            .iter()
+            .find(|&&(_, name)| name.trim_end_matches(';') > module.as_str())
+            .map(|&(index, _)| index)
+            .or_else(|| modules.last().map(|&(index, _)| index + 1))
+            .ok_or(Error::MissingSection("module declarations in lib.rs"))?;
+
+        let days: Vec<(usize, u8)> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| Some((index, registered_day(line)?)))
+            .collect();
+        if days.iter().any(|&(_, day)| day == self.day) {
+            return Err(Error::InvalidInput(format!(
+                "day {} is already registered",
+                self.day
+            )));
+        }
+        let day_index = days
+            .iter()
+            .find(|&&(_, day)| day > self.day)
+            .map(|&(index, _)| index)
+            .or_else(|| days.last().map(|&(index, _)| index + 1))
+            .ok_or(Error::MissingSection("DAYS entries in lib.rs"))?;
+
+        // The day entries come after the module declarations, so insert them first.
+        lines.insert(day_index, &day_line);
+        lines.insert(mod_index, &mod_line);
+
+        let mut source = lines.join("\n");
+        source.push('\n');
+        Ok(source)
+    }
+
+    /// Writes the module, registers it in `src/lib.rs` and creates the input directory with
+    /// empty example files, all relative to the crate root `root`. Returns the paths that
+    /// were written. Existing input files are left alone.
+    pub fn create<P: AsRef<Path>>(&self, root: P) -> Result<Vec<PathBuf>> {
+        let root = root.as_ref();
+        let lib_path = root.join("src").join("lib.rs");
+        let module_path = root.join("src").join(format!("{}.rs", self.module_name()));
+        let input_dir = root
+            .join(INPUT_DIR)
+            .join(format!("{}-{}", self.day, self.name));
+
+        if module_path.exists() {
+            return Err(Error::InvalidInput(format!(
+                "'{}' already exists",
+                module_path.display()
+            )));
+        }
+        let lib_source = self.register(&fs::read_to_string(&lib_path)?)?;
+
+        fs::write(&module_path, self.module_source())?;
+        fs::write(&lib_path, lib_source)?;
+        let mut written = vec![module_path, lib_path];
+
+        fs::create_dir_all(&input_dir)?;
+        for file in EXAMPLE_FILES {
+            let path = input_dir.join(file);
+            if !path.exists() {
+                fs::write(&path, "")?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// The day number of a `Day::new::<...>(<day>, "<name>"),` line of a year's `DAYS`.
+fn registered_day(line: &str) -> Option<u8> {
+    let (_, rest) = line
+        .trim_start()
+        .strip_prefix("Day::new::<")?
+        .split_once(">(")?;
+    let (day, _) = rest.split_once(',')?;
+    day.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod alpha;\npub mod gamma;\n\npub const DAYS: &[Day] = &[\n    Day::new::<alpha::Alpha>(1, \"alpha\"),\n    Day::new::<gamma::Gamma>(20, \"gamma\"),\n];\n";
+
+    #[test]
+    fn test_names() {
+        let day = NewDay::new(15, "warehouse-woes").unwrap();
+        assert_eq!(day.module_name(), "warehouse_woes");
+        assert_eq!(day.type_name(), "WarehouseWoes");
+
+        let source = day.module_source();
+        assert!(source.contains("impl Solution for WarehouseWoes {"));
+        assert!(source.contains("\"input/15-warehouse-woes/test-input-1.txt\""));
+
+        assert!(NewDay::new(26, "warehouse-woes").is_err());
+        assert!(NewDay::new(15, "Warehouse_Woes").is_err());
+        assert!(NewDay::new(15, "2-warehouses").is_err());
+    }
+
+    #[test]
+    fn test_register_keeps_order() {
+        let day = NewDay::new(15, "beta").unwrap();
+        assert_eq!(
+            day.register(LIB).unwrap(),
+            "pub mod alpha;\npub mod beta;\npub mod gamma;\n\npub const DAYS: &[Day] = &[\n    Day::new::<alpha::Alpha>(1, \"alpha\"),\n    Day::new::<beta::Beta>(15, \"beta\"),\n    Day::new::<gamma::Gamma>(20, \"gamma\"),\n];\n"
+        );
+
+        let last = NewDay::new(25, "zeta").unwrap().register(LIB).unwrap();
+        assert!(last.contains("pub mod gamma;\npub mod zeta;\n"));
+        assert!(last.ends_with("(20, \"gamma\"),\n    Day::new::<zeta::Zeta>(25, \"zeta\"),\n];\n"));
+    }
+
+    #[test]
+    fn test_register_rejects_duplicates() {
+        let err = NewDay::new(20, "delta").unwrap().register(LIB).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: day 20 is already registered"
+        );
+
+        let err = NewDay::new(2, "gamma").unwrap().register(LIB).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input: module 'gamma' is already declared"
+        );
+    }
+
+    #[test]
+    fn test_create_in_directory() {
+        let root = std::env::temp_dir().join(format!("aoc2024-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        let day = NewDay::new(15, "beta").unwrap();
+        let written = day.create(&root).unwrap();
+        let err = day.create(&root).unwrap_err();
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        let example = root.join("input/15-beta/test-input-1.txt").exists();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(written.len(), 4);
+        assert!(lib.contains("pub mod beta;"));
+        assert!(example);
+        assert!(err.to_string().contains("already exists"));
+    }
+}
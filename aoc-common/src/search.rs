@@ -0,0 +1,393 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// What a BFS or DFS visitor wants the search to do after seeing a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Keep going and explore the node's neighbors.
+    Continue,
+    /// Keep going, but do not explore past this node.
+    Prune,
+    /// End the search.
+    Stop,
+}
+
+/// Breadth-first search from `start`. Every reachable node is visited once, together with
+/// its distance in steps from `start`, in order of increasing distance.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut visit: impl FnMut(&N, usize) -> Control,
+) where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, depth)) = queue.pop_front() {
+        match visit(&node, depth) {
+            Control::Continue => {}
+            Control::Prune => continue,
+            Control::Stop => return,
+        }
+        for next in neighbors(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+}
+
+/// Depth-first search from `start`. Every reachable node is visited once, together with
+/// the length of the path the search took to reach it.
+pub fn dfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut visit: impl FnMut(&N, usize) -> Control,
+) where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut stack = vec![(start, 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+        match visit(&node, depth) {
+            Control::Continue => {}
+            Control::Prune => continue,
+            Control::Stop => return,
+        }
+
+        let next: Vec<N> = neighbors(&node).into_iter().collect();
+        // Pushed in reverse so neighbors are explored in the order they were given.
+        for next in next.into_iter().rev() {
+            if !seen.contains(&next) {
+                stack.push((next, depth + 1));
+            }
+        }
+    }
+}
+
+/// Every node reachable from `start`, `start` included.
+pub fn reachable<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut found = HashSet::new();
+    bfs(start, neighbors, |node, _| {
+        found.insert(node.clone());
+        Control::Continue
+    });
+    found
+}
+
+/// Counts the distinct paths from `start` to any goal node in a directed acyclic graph.
+/// Paths end at the first goal they reach.
+///
+/// `memo` holds the counts of nodes already solved, so it can be shared between calls that
+/// search the same graph from different starts.
+pub fn count_paths<N, I>(
+    start: N,
+    neighbors: &mut impl FnMut(&N) -> I,
+    is_goal: &mut impl FnMut(&N) -> bool,
+    memo: &mut HashMap<N, usize>,
+) -> usize
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    if let Some(&count) = memo.get(&start) {
+        return count;
+    }
+
+    let count = if is_goal(&start) {
+        1
+    } else {
+        let next: Vec<N> = neighbors(&start).into_iter().collect();
+        next.into_iter()
+            .map(|next| count_paths(next, neighbors, is_goal, memo))
+            .sum()
+    };
+
+    memo.insert(start, count);
+    count
+}
+
+/// Cheapest path from `start` to the first goal node, where `neighbors` yields each
+/// neighbor with the cost of stepping onto it. Returns the cost and the path, both ends
+/// included.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], guided by `heuristic`, an estimate of the remaining cost to a goal.
+/// The path is only guaranteed to be the cheapest if the heuristic never overestimates.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Nodes are numbered as they are discovered so the heap does not need `N: Ord`.
+    let mut nodes = vec![start.clone()];
+    let mut index = HashMap::from([(start, 0)]);
+    let mut best = vec![C::default()];
+    let mut parent: Vec<Option<usize>> = vec![None];
+
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&nodes[0]), C::default(), 0)));
+
+    while let Some(Reverse((_, cost, current))) = heap.pop() {
+        if cost > best[current] {
+            continue;
+        }
+        if is_goal(&nodes[current]) {
+            let mut path = vec![nodes[current].clone()];
+            let mut at = current;
+            while let Some(previous) = parent[at] {
+                path.push(nodes[previous].clone());
+                at = previous;
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        let node = nodes[current].clone();
+        for (next, step_cost) in neighbors(&node) {
+            let next_cost = cost + step_cost;
+            let next_index = match index.get(&next) {
+                Some(&known) if next_cost >= best[known] => continue,
+                Some(&known) => {
+                    best[known] = next_cost;
+                    parent[known] = Some(current);
+                    known
+                }
+                None => {
+                    index.insert(next.clone(), nodes.len());
+                    nodes.push(next);
+                    best.push(next_cost);
+                    parent.push(Some(current));
+                    nodes.len() - 1
+                }
+            };
+            let estimate = next_cost + heuristic(&nodes[next_index]);
+            heap.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+/// Returned by [`topological_sort`] when the edges contain a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    /// The nodes that could be ordered before the cycle got in the way.
+    pub sorted: Vec<N>,
+    /// One cycle, in edge order; the first node follows the last one.
+    pub cycle: Vec<N>,
+}
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle ")?;
+        for node in &self.cycle {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Orders `nodes` so that for every edge `(before, after)` between two of them, `before`
+/// comes first. Whenever several nodes could come next, the one listed first in `nodes`
+/// wins, so already ordered input is returned unchanged. Edges that mention other nodes
+/// are ignored.
+pub fn topological_sort<N>(
+    nodes: &[N],
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Clone + Eq + Hash,
+{
+    let position: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, n)| (n, i)).collect();
+    let mut successors = vec![Vec::new(); nodes.len()];
+    let mut predecessors = vec![Vec::new(); nodes.len()];
+    let mut in_degree = vec![0; nodes.len()];
+
+    for (before, after) in edges {
+        if let (Some(&from), Some(&to)) = (position.get(&before), position.get(&after)) {
+            successors[from].push(to);
+            predecessors[to].push(from);
+            in_degree[to] += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(nodes.len());
+
+    while let Some(Reverse(current)) = ready.pop() {
+        order.push(current);
+        for &next in &successors[current] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+
+    let sorted: Vec<N> = order.iter().map(|&i| nodes[i].clone()).collect();
+    if order.len() == nodes.len() {
+        return Ok(sorted);
+    }
+
+    // Every node left over still has a predecessor that is left over, so walking
+    // backwards along those edges must eventually run into a node seen before.
+    let mut walk = vec![(0..nodes.len())
+        .find(|&i| in_degree[i] > 0)
+        .unwrap_or_default()];
+    let mut seen_at = HashMap::from([(walk[0], 0)]);
+    let start = loop {
+        let current = walk[walk.len() - 1];
+        let previous = predecessors[current]
+            .iter()
+            .copied()
+            .find(|&p| in_degree[p] > 0)
+            .unwrap_or(current);
+        if let Some(&at) = seen_at.get(&previous) {
+            break at;
+        }
+        seen_at.insert(previous, walk.len());
+        walk.push(previous);
+    };
+
+    let cycle = walk[start..]
+        .iter()
+        .rev()
+        .map(|&i| nodes[i].clone())
+        .collect();
+    Err(Cycle { sorted, cycle })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Edges of a small graph: 1 -> 2 -> 4, 1 -> 3 -> 4, 4 -> 5.
+    fn diamond(node: &u32) -> Vec<u32> {
+        match node {
+            1 => vec![2, 3],
+            2 | 3 => vec![4],
+            4 => vec![5],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs_visits_by_distance() {
+        let mut visited = Vec::new();
+        bfs(1, diamond, |&node, depth| {
+            visited.push((node, depth));
+            Control::Continue
+        });
+        assert_eq!(visited, vec![(1, 0), (2, 1), (3, 1), (4, 2), (5, 3)]);
+
+        let mut visited = Vec::new();
+        bfs(1, diamond, |&node, _| {
+            visited.push(node);
+            if node == 2 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!(visited, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_dfs_prunes_and_visits_once() {
+        let mut visited = Vec::new();
+        dfs(1, diamond, |&node, _| {
+            visited.push(node);
+            if node == 4 {
+                Control::Prune
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!(visited, vec![1, 2, 4, 3]);
+        assert_eq!(reachable(3, diamond), HashSet::from([3, 4, 5]));
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let mut memo = HashMap::new();
+        assert_eq!(count_paths(1, &mut diamond, &mut |&n| n == 5, &mut memo), 2);
+        assert_eq!(count_paths(3, &mut diamond, &mut |&n| n == 5, &mut memo), 1);
+        assert_eq!(
+            count_paths(5, &mut diamond, &mut |&n| n == 1, &mut HashMap::new()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // A line of cells 0..=9 where stepping right costs 1 and jumping 3 costs 2.
+        let neighbors = |&n: &i32| {
+            [(n + 1, 1), (n + 3, 2)]
+                .into_iter()
+                .filter(|&(next, _)| next <= 9)
+        };
+
+        let (cost, path) = dijkstra(0, neighbors, |&n| n == 9).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path, vec![0, 3, 6, 9]);
+
+        let (cost, _) = astar(0, neighbors, |&n| (9 - n) / 3, |&n| n == 9).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(dijkstra(0, neighbors, |&n| n == 10), None);
+    }
+
+    #[test]
+    fn test_topological_sort_is_stable() {
+        let sorted = topological_sort(&[75, 97, 47, 61, 53], [(97, 75), (75, 47), (13, 47)]);
+        assert_eq!(sorted, Ok(vec![97, 75, 47, 61, 53]));
+
+        let sorted = topological_sort(&[1, 2, 3], []);
+        assert_eq!(sorted, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_topological_sort_reports_cycles() {
+        let err = topological_sort(&[1, 2, 3, 4], [(1, 2), (2, 3), (3, 2), (3, 4)]).unwrap_err();
+        assert_eq!(err.sorted, vec![1]);
+        assert_eq!(err.cycle.len(), 2);
+        assert_eq!(err.to_string().matches("->").count(), 2);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::error::{Error, Result};
+use crate::input::{open_file, read_input};
+use crate::rng::Rng;
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        i128::try_from(value)
+            .map(Answer::Number)
+            .unwrap_or_else(|_| Answer::Text(value.to_string()))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// A day's puzzle: the input is parsed once and both parts are answered from it.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    /// Parses an input read from any buffered reader, e.g. stdin or an in-memory buffer.
+    fn parse_reader<R: BufRead>(reader: R) -> Result<Self::Input> {
+        Self::parse(&read_input(reader)?)
+    }
+
+    /// Parses the input stored in a file.
+    fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self::Input> {
+        Self::parse_reader(open_file(path)?)
+    }
+
+    /// How many parts are solved: 1 until [`Solution::part2`] is implemented.
+    const PARTS: u8 = 1;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    /// Reports [`Error::NotImplemented`] until the second part has been solved, which also
+    /// raises [`Solution::PARTS`] to 2.
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        Err(Error::NotImplemented { part: 2 })
+    }
+
+    /// A random input that [`Solution::parse`] accepts, for stress tests; `size` scales it,
+    /// e.g. the number of lines or the side of a grid. `None` for days without a generator.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// A parsed input with the day's type erased, so every day can be driven the same way.
+pub trait Puzzle {
+    fn part1(&self) -> Result<Answer>;
+    fn part2(&self) -> Result<Answer>;
+
+    /// Answers the given part; parts other than 1 and 2 are never implemented.
+    fn part(&self, part: u8) -> Result<Answer> {
+        match part {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => Err(Error::NotImplemented { part }),
+        }
+    }
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> Puzzle for Parsed<S> {
+    fn part1(&self) -> Result<Answer> {
+        S::part1(&self.0)
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        S::part2(&self.0)
+    }
+}
+
+fn parse_erased<S: Solution + 'static>(input: &str) -> Result<Box<dyn Puzzle>> {
+    Ok(Box::new(Parsed::<S>(S::parse(input)?)))
+}
+
+/// A registry entry: the day number, its puzzle name, the parts solved so far, how to
+/// parse its input and how to generate random inputs.
+pub struct Day {
+    pub number: u8,
+    pub name: &'static str,
+    /// Parts 1 to `parts` are solved.
+    pub parts: u8,
+    pub parse: fn(&str) -> Result<Box<dyn Puzzle>>,
+    pub generate: fn(&mut Rng, usize) -> Option<String>,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>(number: u8, name: &'static str) -> Self {
+        Day {
+            number,
+            name,
+            parts: S::PARTS,
+            parse: parse_erased::<S>,
+            generate: S::generate,
+        }
+    }
+
+    /// Fails with [`Error::MissingPart`] unless `part` is one of the solved parts.
+    pub fn check_part(&self, part: u8) -> Result<()> {
+        if (1..=self.parts).contains(&part) {
+            Ok(())
+        } else {
+            Err(Error::MissingPart {
+                day: self.number,
+                name: self.name,
+                part,
+            })
+        }
+    }
+
+    /// Parses an input read from any buffered reader.
+    pub fn parse_reader(&self, reader: impl BufRead) -> Result<Box<dyn Puzzle>> {
+        (self.parse)(&read_input(reader)?)
+    }
+
+    /// Parses the input stored in a file.
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<Box<dyn Puzzle>> {
+        self.parse_reader(open_file(path)?)
+    }
+}
+
+/// Looks up a day by number in a registry.
+pub fn find_day(days: &[Day], number: u8) -> Result<&Day> {
+    days.iter()
+        .find(|day| day.number == number)
+        .ok_or(Error::UnknownDay(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Doubler;
+
+    impl Solution for Doubler {
+        type Input = i64;
+
+        fn parse(input: &str) -> Result<i64> {
+            input
+                .trim()
+                .parse()
+                .map_err(|_| Error::InvalidInput(input.to_string()))
+        }
+
+        fn part1(input: &i64) -> Result<Answer> {
+            Ok((input * 2).into())
+        }
+    }
+
+    #[test]
+    fn test_registry_dispatch() {
+        let days = [Day::new::<Doubler>(1, "doubler")];
+        let day = find_day(&days, 1).unwrap();
+        let puzzle = (day.parse)("21\n").unwrap();
+
+        assert_eq!(puzzle.part(1).unwrap(), Answer::Number(42));
+        assert!(matches!(
+            puzzle.part(2),
+            Err(Error::NotImplemented { part: 2 })
+        ));
+        assert!(matches!(find_day(&days, 2), Err(Error::UnknownDay(2))));
+        assert_eq!(day.parts, 1);
+        assert!(day.check_part(1).is_ok());
+        assert_eq!(
+            day.check_part(2).unwrap_err().to_string(),
+            "day 1 (doubler) has no part 2 yet"
+        );
+        assert!((day.parse)("x").is_err());
+        assert_eq!((day.generate)(&mut Rng::new(1), 5), None);
+    }
+
+    #[test]
+    fn test_parse_from_reader() {
+        assert_eq!(Doubler::parse_reader("5\n".as_bytes()).unwrap(), 5);
+
+        let day = Day::new::<Doubler>(1, "doubler");
+        let puzzle = day.parse_reader(std::io::Cursor::new("8")).unwrap();
+        assert_eq!(puzzle.part(1).unwrap(), Answer::Number(16));
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(1928u128).to_string(), "1928");
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("abc".to_string()).to_string(), "abc");
+    }
+}
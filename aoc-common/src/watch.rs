@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Modification time and size of a file, or `None` while it does not exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// The state of a set of files at one point in time. Comparing two snapshots taken some
+/// time apart tells which files were written, created or removed in between.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    stamps: BTreeMap<PathBuf, Stamp>,
+}
+
+impl Snapshot {
+    pub fn take<I: IntoIterator<Item = PathBuf>>(paths: I) -> Self {
+        let stamps = paths
+            .into_iter()
+            .map(|path| {
+                let stamp = fs::metadata(&path)
+                    .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                    .ok();
+                (path, stamp)
+            })
+            .collect();
+        Snapshot { stamps }
+    }
+
+    /// Files whose stamp differs from `earlier`, including files only one of them knows.
+    pub fn changed_since<'a>(&'a self, earlier: &'a Snapshot) -> Vec<&'a Path> {
+        let mut changed: Vec<&Path> = self
+            .stamps
+            .iter()
+            .filter(|(path, stamp)| earlier.stamps.get(*path) != Some(stamp))
+            .map(|(path, _)| path.as_path())
+            .collect();
+        changed.extend(
+            earlier
+                .stamps
+                .keys()
+                .filter(|path| !self.stamps.contains_key(*path))
+                .map(PathBuf::as_path),
+        );
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_detects_changes() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input-1.txt");
+        let added = dir.join("input-2.txt");
+        fs::write(&input, "1 2\n").unwrap();
+
+        let before = Snapshot::take([input.clone()]);
+        let unchanged = Snapshot::take([input.clone()])
+            .changed_since(&before)
+            .is_empty();
+        fs::write(&input, "1 2 3\n").unwrap();
+        fs::write(&added, "").unwrap();
+        let after = Snapshot::take([input.clone(), added.clone()]);
+        let changed: Vec<PathBuf> = after
+            .changed_since(&before)
+            .into_iter()
+            .map(Path::to_path_buf)
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(unchanged);
+        assert_eq!(changed, vec![input.clone(), added]);
+        assert_eq!(
+            Snapshot::take([dir.join("missing.txt")]).changed_since(&after),
+            vec![
+                dir.join("missing.txt").as_path(),
+                input.as_path(),
+                dir.join("input-2.txt").as_path()
+            ]
+        );
+    }
+}